@@ -0,0 +1,83 @@
+//! Compares the wildcard-pattern graph construction against the original
+//! pairwise construction on dictionaries of increasing size. Run with
+//! `cargo bench`.
+//!
+//! The dictionaries are generated from a fixed seed over a reduced alphabet so
+//! that they are about as densely connected as a real word list of the same
+//! size would be, and so that every run measures the same input.
+
+use std::collections::BTreeSet;
+use std::time::{Duration, Instant};
+
+use weavesolve::WordGraph;
+
+/// Sizes at which both constructions are timed
+const COMPARED_SIZES: [usize; 3] = [1_000, 5_000, 20_000];
+
+/// Larger sizes at which only the wildcard-pattern construction is timed, since
+/// the pairwise one would take minutes
+const LARGE_SIZES: [usize; 2] = [100_000, 250_000];
+
+const WORD_LEN: usize = 6;
+
+/// A small xorshift generator, so the benchmark needs no extra dependencies
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+}
+
+/// Generates `size` distinct words of `WORD_LEN` letters drawn from the first
+/// ten letters of the alphabet
+fn generate_dict(size: usize) -> Vec<String> {
+    let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+    let mut words = BTreeSet::new();
+    while words.len() < size {
+        let word: String = (0..WORD_LEN)
+            .map(|_| (b'a' + (rng.next() % 10) as u8) as char)
+            .collect();
+        words.insert(word);
+    }
+
+    words.into_iter().collect()
+}
+
+/// Times a single graph construction
+fn time<'a>(build: impl FnOnce() -> WordGraph<'a>) -> (WordGraph<'a>, Duration) {
+    let now = Instant::now();
+    let graph = build();
+    (graph, now.elapsed())
+}
+
+fn main() {
+    println!("{:>8} {:>14} {:>14} {:>8}", "words", "pairwise", "patterns", "speedup");
+    for size in COMPARED_SIZES {
+        let words = generate_dict(size);
+        let dict: Vec<&str> = words.iter().map(String::as_str).collect();
+
+        let (pairwise, pairwise_time) = time(|| WordGraph::from_dict_pairwise(&dict));
+        let (patterns, patterns_time) = time(|| WordGraph::from_dict(&dict));
+        assert_eq!(pairwise, patterns, "the two constructions disagree at {} words", size);
+
+        println!(
+            "{:>8} {:>14?} {:>14?} {:>7.1}x",
+            size,
+            pairwise_time,
+            patterns_time,
+            pairwise_time.as_secs_f64() / patterns_time.as_secs_f64()
+        );
+    }
+
+    for size in LARGE_SIZES {
+        let words = generate_dict(size);
+        let dict: Vec<&str> = words.iter().map(String::as_str).collect();
+
+        let (_, patterns_time) = time(|| WordGraph::from_dict(&dict));
+        println!("{:>8} {:>14} {:>14?} {:>8}", size, "-", patterns_time, "-");
+    }
+}
@@ -0,0 +1,83 @@
+//! Precomputes the word graph of the built-in dictionary, so that the binary
+//! starts with it ready rather than building it on every run. The words are
+//! read straight out of `src/dict.rs`, and the graph is written to
+//! `$OUT_DIR/dict_graph.rs` in the same compressed sparse row form the
+//! library uses: the neighbours of word `id` are
+//! `DICT_TARGETS[DICT_OFFSETS[id]..DICT_OFFSETS[id + 1]]`.
+
+use std::collections::HashMap;
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+/// Reads the words of `DICT` out of the source of `src/dict.rs`, which lists
+/// them as string literals between the brackets of the array
+fn dict_words(source: &str) -> Vec<&str> {
+    let start = source.find("= [").expect("src/dict.rs should define DICT as an array") + 3;
+    let end = start + source[start..].find(']').expect("the DICT array should be closed");
+    source[start..end].split(',').map(|word| word.trim().trim_matches('"')).filter(|word| !word.is_empty()).collect()
+}
+
+/// The neighbours of every word, in dictionary order, where two words are
+/// neighbours when they differ by a single letter. Like the library, this
+/// files each word under its wildcard patterns rather than comparing pairs.
+fn neighbors(words: &[&str]) -> Vec<Vec<u32>> {
+    let mut buckets: HashMap<String, Vec<u32>> = HashMap::new();
+    for (id, word) in words.iter().enumerate() {
+        for i in 0..word.len() {
+            let pattern = format!("{}_{}", &word[..i], &word[i + 1..]);
+            buckets.entry(pattern).or_default().push(id as u32);
+        }
+    }
+    let mut neighbors = vec![Vec::new(); words.len()];
+    for bucket in buckets.values() {
+        for &id in bucket {
+            neighbors[id as usize].extend(bucket.iter().filter(|&&other| other != id));
+        }
+    }
+    for connections in neighbors.iter_mut() {
+        connections.sort_unstable();
+        connections.dedup();
+    }
+
+    neighbors
+}
+
+/// Writes `values` as a static array named `name`
+fn write_array(out: &mut String, name: &str, values: &[u32]) {
+    writeln!(out, "static {}: [u32; {}] = [", name, values.len()).unwrap();
+    for line in values.chunks(16) {
+        let line: Vec<String> = line.iter().map(u32::to_string).collect();
+        writeln!(out, "    {},", line.join(",")).unwrap();
+    }
+    writeln!(out, "];").unwrap();
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed=src/dict.rs");
+    println!("cargo:rerun-if-changed=build.rs");
+
+    let source = fs::read_to_string("src/dict.rs").expect("src/dict.rs should be readable");
+    let words = dict_words(&source);
+    // a loaded dictionary is always sorted and free of repeats, so only a
+    // DICT that already is one gives the same words as Dictionary::builtin
+    assert!(words.windows(2).all(|pair| pair[0] < pair[1]), "the words of DICT must be sorted and must not repeat");
+    assert!(
+        words.iter().all(|word| !word.is_empty() && word.bytes().all(|c| c.is_ascii_lowercase())),
+        "the words of DICT must only use the letters a-z"
+    );
+
+    let mut offsets = vec![0];
+    let mut targets = Vec::new();
+    for connections in neighbors(&words) {
+        targets.extend(connections);
+        offsets.push(targets.len() as u32);
+    }
+
+    let mut out = String::new();
+    write_array(&mut out, "DICT_OFFSETS", &offsets);
+    write_array(&mut out, "DICT_TARGETS", &targets);
+    let path = Path::new(&env::var("OUT_DIR").unwrap()).join("dict_graph.rs");
+    fs::write(path, out).expect("the precomputed graph should be writable");
+}
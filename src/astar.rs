@@ -0,0 +1,185 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::mem;
+
+use crate::bitset::BitSet;
+use crate::graph::{Subgraph, WordId};
+use crate::moves::{MoveKind, MoveSet};
+use crate::search::{walk_parents, SearchStats, NO_PARENT};
+
+/// The number of positions at which two words of the same length have
+/// different letters. Every move on a ladder changes exactly one letter, so
+/// a word can never be fewer moves from the goal than this, which makes it an
+/// admissible (and consistent) heuristic for A* and IDA*.
+pub(crate) fn hamming_distance(s1: &str, s2: &str) -> u32 {
+    s1.bytes().zip(s2.bytes()).filter(|(c1, c2)| c1 != c2).count() as u32
+}
+
+/// The fewest edits (substitutions, insertions and deletions of a single
+/// letter) that turn one word into the other. With insertions and deletions
+/// allowed as moves, words on a ladder can have different lengths and the
+/// Hamming distance no longer makes sense, but each move is still a single
+/// edit, so this is admissible (and consistent) in its place.
+pub(crate) fn edit_distance(s1: &str, s2: &str) -> u32 {
+    let (s1, s2) = (s1.as_bytes(), s2.as_bytes());
+    let mut previous: Vec<u32> = (0..=s2.len() as u32).collect();
+    let mut current = vec![0; s2.len() + 1];
+    for (i, &c1) in s1.iter().enumerate() {
+        current[0] = i as u32 + 1;
+        for (j, &c2) in s2.iter().enumerate() {
+            let substitution = previous[j] + (c1 != c2) as u32;
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        mem::swap(&mut previous, &mut current);
+    }
+
+    previous[s2.len()]
+}
+
+/// The number of letters of one word that have no match in the other,
+/// counting from whichever side has more: the fewest substitutions, insertions
+/// and deletions needed to turn one word into an anagram of the other.
+/// Rearranging letters doesn't change this at all and every other move changes
+/// it by at most one, so it is admissible (and consistent) when anagrams are
+/// allowed as moves.
+pub(crate) fn letter_distance(s1: &str, s2: &str) -> u32 {
+    let mut counts = [0i32; 256];
+    for c in s1.bytes() {
+        counts[c as usize] += 1;
+    }
+    for c in s2.bytes() {
+        counts[c as usize] -= 1;
+    }
+    let extra: i32 = counts.iter().filter(|&&count| count > 0).sum();
+    let missing: i32 = -counts.iter().filter(|&&count| count < 0).sum::<i32>();
+
+    extra.max(missing) as u32
+}
+
+/// A lower bound on the number of moves between two words that suits the
+/// kinds of move the graph was built with. A swap of neighbouring letters
+/// changes two positions at once, and counts as at most two edits, so with
+/// swaps allowed the distance is halved, rounding up.
+pub(crate) fn lower_bound(moves: MoveSet, s1: &str, s2: &str) -> u32 {
+    if moves.contains(MoveKind::Anagram) {
+        return letter_distance(s1, s2)
+    }
+    let distance = if moves.changes_length() { edit_distance(s1, s2) } else { hamming_distance(s1, s2) };
+    if moves.contains(MoveKind::Swap) {
+        distance.div_ceil(2)
+    } else {
+        distance
+    }
+}
+
+/// A* search guided by the Hamming distance to the goal, or whichever
+/// `lower_bound` suits the moves. Words are expanded in order of `f = g + h`,
+/// the length of the ladder so far plus the fewest changes that could possibly
+/// still be needed, with ties going to the word closest to the goal. Because
+/// the heuristic is consistent, the first time the goal comes off the heap its
+/// ladder is a shortest one.
+pub(crate) fn astar(graph: Subgraph, start: WordId, goal: WordId, stats: &mut SearchStats) -> Option<Vec<WordId>> {
+    let goal_word = graph.word(goal);
+    let moves = graph.moves();
+    let h = |id: WordId| lower_bound(moves, graph.word(id), goal_word);
+
+    let mut g_score = vec![u32::MAX; graph.len()];
+    let mut parent_map = vec![NO_PARENT; graph.len()];
+    let mut closed = BitSet::new(graph.len());
+    let mut open = BinaryHeap::new();
+    g_score[start as usize] = 0;
+    open.push(Reverse((h(start), h(start), start)));
+    while let Some(Reverse((_, _, v))) = open.pop() {
+        if v == goal {
+            return Some(walk_parents(&parent_map, start, goal))
+        }
+        // a word can be pushed more than once before it is expanded; only the
+        // first, cheapest copy counts
+        if !closed.insert(v) {
+            continue
+        }
+        stats.expanded += 1;
+
+        let g = g_score[v as usize] + 1;
+        for w in graph.neighbors(v) {
+            if g < g_score[w as usize] {
+                g_score[w as usize] = g;
+                parent_map[w as usize] = v;
+                open.push(Reverse((g + h(w), h(w), w)));
+            }
+        }
+    }
+
+    None
+}
+
+/// Iterative-deepening A*: a depth-first search that gives up on any ladder
+/// whose `f = g + h` exceeds a bound, raising the bound to the smallest `f`
+/// that was cut off each time a pass fails. Only the current ladder is kept in
+/// memory, at the price of expanding some words again on every pass.
+pub(crate) fn ida_star(graph: Subgraph, start: WordId, goal: WordId, stats: &mut SearchStats) -> Option<Vec<WordId>> {
+    let goal_word = graph.word(goal);
+    let moves = graph.moves();
+    let h = |id: WordId| lower_bound(moves, graph.word(id), goal_word);
+
+    let mut path = vec![start];
+    let mut bound = h(start);
+    loop {
+        match ida_star_pass(graph, goal, &h, &mut path, bound, stats) {
+            Pass::Found => return Some(path),
+            Pass::CutOff(next_bound) => bound = next_bound,
+            Pass::Exhausted => return None,
+        }
+    }
+}
+
+/// How a single bounded pass of IDA* ended
+enum Pass {
+    /// The goal was reached and the ladder is left in `path`
+    Found,
+    /// Some ladders went over the bound; the smallest `f` among them
+    CutOff(u32),
+    /// Every ladder was followed to its end without going over the bound, so
+    /// there is no ladder at all
+    Exhausted,
+}
+
+/// One depth-first pass of IDA* from the last word of `path`
+fn ida_star_pass(
+    graph: Subgraph,
+    goal: WordId,
+    h: &impl Fn(WordId) -> u32,
+    path: &mut Vec<WordId>,
+    bound: u32,
+    stats: &mut SearchStats,
+) -> Pass {
+    let v = path[path.len() - 1];
+    let f = (path.len() - 1) as u32 + h(v);
+    if f > bound {
+        return Pass::CutOff(f)
+    }
+    if v == goal {
+        return Pass::Found
+    }
+    stats.expanded += 1;
+
+    let mut next_bound = None;
+    for w in graph.neighbors(v) {
+        // a shortest ladder never visits a word twice
+        if path.contains(&w) {
+            continue
+        }
+        path.push(w);
+        match ida_star_pass(graph, goal, h, path, bound, stats) {
+            Pass::Found => return Pass::Found,
+            Pass::CutOff(f) => next_bound = Some(next_bound.map_or(f, |bound: u32| bound.min(f))),
+            Pass::Exhausted => (),
+        }
+        path.pop();
+    }
+
+    match next_bound {
+        Some(bound) => Pass::CutOff(bound),
+        None => Pass::Exhausted,
+    }
+}
@@ -0,0 +1,199 @@
+use std::fs;
+use std::io::{self, Read};
+use std::mem;
+use std::path::Path;
+use std::process::ExitCode;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use weavesolve::{SolveError, WordFrequencies};
+
+use crate::json::Json;
+use crate::{csv_row, empty_row, exit_code, query, Format, Output, Report};
+
+/// Exit code used when a line of `batch` input isn't a pair of words
+const MALFORMED_LINE: u8 = 14;
+
+/// A line of `batch` input
+pub enum Line {
+    /// A starting and an ending word
+    Pair(String, String),
+    /// A line with more or fewer than two words on it, and its number,
+    /// counting from one
+    Malformed(usize, String),
+}
+
+impl Line {
+    /// The words of the line, if it is a pair
+    pub fn words(&self) -> impl Iterator<Item = &String> {
+        let pair = match self {
+            Line::Pair(start, goal) => Some([start, goal]),
+            Line::Malformed(..) => None,
+        };
+        pair.into_iter().flatten()
+    }
+}
+
+/// Splits `batch` input into lines of a starting and an ending word,
+/// separated by whitespace or a comma, so that both plain lists and CSV files
+/// can be read. Blank lines and lines starting with `#` are skipped, as is a
+/// `start,goal` header on the first line that isn't.
+fn parse_lines(input: &str) -> Vec<Line> {
+    let mut lines = Vec::new();
+    let mut first = true;
+    for (i, line) in input.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue
+        }
+        let header = mem::replace(&mut first, false);
+        let words: Vec<&str> = line
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|word| !word.is_empty())
+            .map(|word| word.trim_matches('"'))
+            .collect();
+        match words[..] {
+            ["start", "goal"] if header => {}
+            [start, goal] => lines.push(Line::Pair(start.to_string(), goal.to_string())),
+            _ => lines.push(Line::Malformed(i + 1, line.to_string())),
+        }
+    }
+
+    lines
+}
+
+/// Reads the lines of `batch` input from a file, or from stdin if the path is
+/// `-`
+pub fn read_lines(path: &Path) -> io::Result<Vec<Line>> {
+    let input = if path == Path::new("-") {
+        let mut input = String::new();
+        io::stdin().read_to_string(&mut input)?;
+        input
+    } else {
+        fs::read_to_string(path)?
+    };
+
+    Ok(parse_lines(&input))
+}
+
+/// Answers the query for every pair of words with `jobs` threads, each taking
+/// the next pair nobody has started on yet, and returns the answers in the
+/// order of the pairs along with how long each one took
+fn query_all<'a>(
+    output: &Output<'a>,
+    frequencies: &WordFrequencies,
+    pairs: &[[&str; 2]],
+    jobs: usize,
+) -> Vec<(Result<Report<'a>, SolveError>, Duration)> {
+    let next = AtomicUsize::new(0);
+    let work = || {
+        let mut answers = Vec::new();
+        loop {
+            let i = next.fetch_add(1, Ordering::Relaxed);
+            let Some(&[start, goal]) = pairs.get(i) else { break };
+            let started = Instant::now();
+            let result = query(output.graph, output.cli, frequencies, start, goal);
+            answers.push((i, result, started.elapsed()));
+        }
+        answers
+    };
+
+    let mut answers: Vec<_> = thread::scope(|scope| {
+        let workers: Vec<_> = (0..jobs.clamp(1, pairs.len().max(1))).map(|_| scope.spawn(work)).collect();
+        workers.into_iter().flat_map(|worker| worker.join().unwrap()).collect()
+    });
+    answers.sort_unstable_by_key(|&(i, _, _)| i);
+
+    answers.into_iter().map(|(_, result, time)| (result, time)).collect()
+}
+
+/// Answers the query for every pair of words in `lines`, and prints the
+/// answers in the order of the lines, one after the other. Failures are
+/// printed like answers are, so one bad pair doesn't stop the rest, and the
+/// exit code is that of the first failure.
+pub fn solve(output: &Output, frequencies: &WordFrequencies, lines: &[Line], jobs: usize) -> ExitCode {
+    let jobs = match jobs {
+        0 => thread::available_parallelism().map_or(1, |n| n.get()),
+        jobs => jobs,
+    };
+    let pairs: Vec<[&str; 2]> = lines
+        .iter()
+        .filter_map(|line| match line {
+            Line::Pair(start, goal) => Some([start.as_str(), goal.as_str()]),
+            Line::Malformed(..) => None,
+        })
+        .collect();
+    let mut answers = query_all(output, frequencies, &pairs, jobs).into_iter().zip(pairs);
+
+    let mut code = None;
+    output.print_header();
+    for line in lines {
+        match line {
+            Line::Pair(..) => {
+                let ((result, time), words) = answers.next().unwrap();
+                match (&result, output.cli.format) {
+                    (Err(err), Format::Text) => eprintln!("{} {}: {}", words[0], words[1], err),
+                    _ => output.print(&result, words, time),
+                }
+                if let Err(err) = &result {
+                    code.get_or_insert(exit_code(err));
+                }
+            }
+            Line::Malformed(number, text) => {
+                let message = format!("line {}: expected a starting and an ending word, found {:?}", number, text);
+                match output.cli.format {
+                    Format::Text => eprintln!("{}", message),
+                    Format::Json => {
+                        let error = Json::Object(vec![
+                            ("kind", Json::string("malformed-line")),
+                            ("message", Json::String(message)),
+                            ("exit_code", Json::number(MALFORMED_LINE)),
+                        ]);
+                        println!("{}", Json::Object(vec![("line", Json::number(number)), ("error", error)]));
+                    }
+                    Format::Csv => {
+                        // there is no pair of words, so the line itself goes
+                        // where the starting word would, to trace it back
+                        let mut row = empty_row([text, ""]);
+                        row[6] = "malformed-line".to_string();
+                        println!("{}", csv_row(&row));
+                    }
+                }
+                code.get_or_insert(MALFORMED_LINE);
+            }
+        }
+    }
+
+    code.map_or(ExitCode::SUCCESS, ExitCode::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The pairs of the lines, with malformed lines as their numbers
+    fn pairs(input: &str) -> Vec<Result<(String, String), usize>> {
+        parse_lines(input)
+            .into_iter()
+            .map(|line| match line {
+                Line::Pair(start, goal) => Ok((start, goal)),
+                Line::Malformed(number, _) => Err(number),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn header_is_skipped_after_comments_and_blank_lines() {
+        let expected = vec![Ok(("word".to_string(), "dork".to_string()))];
+        assert_eq!(pairs("start,goal\nword,dork\n"), expected);
+        assert_eq!(pairs("# pairs to solve\n\n\"start\",\"goal\"\nword,dork\n"), expected);
+        assert_eq!(pairs("word dork\nstart goal\n").len(), 2);
+    }
+
+    #[test]
+    fn malformed_lines_keep_their_numbers() {
+        let found = pairs("# pairs\nword dork\n\ncold\ncold warm hot\n");
+        assert_eq!(found[1..], [Err(4), Err(5)]);
+    }
+}
@@ -0,0 +1,186 @@
+use crate::bitset::BitSet;
+use crate::graph::{Subgraph, WordId};
+use crate::search::{SearchStats, UNREACHED};
+
+/// One half of a bidirectional search: the words it has reached and how far
+/// each one is from where this side started
+struct Side {
+    frontier: Vec<WordId>,
+    distance: Vec<u32>,
+    depth: u32,
+}
+
+impl Side {
+    fn new(root: WordId, len: usize) -> Self {
+        let mut distance = vec![UNREACHED; len];
+        distance[root as usize] = 0;
+        Side { frontier: vec![root], distance, depth: 0 }
+    }
+
+    /// Expands every word in the frontier by one layer. Whenever a newly
+    /// reached word has already been reached by the `other` side, the two
+    /// halves of the search meet there; the meeting that gives the shortest
+    /// total ladder is returned once the whole layer is done.
+    fn expand(&mut self, graph: Subgraph, other: &Side, stats: &mut SearchStats) -> Option<WordId> {
+        let mut next = Vec::new();
+        let mut best: Option<(u32, WordId)> = None;
+        for &v in self.frontier.iter() {
+            stats.expanded += 1;
+            for w in graph.neighbors(v) {
+                if self.distance[w as usize] != UNREACHED {
+                    continue
+                }
+                self.distance[w as usize] = self.depth + 1;
+                next.push(w);
+
+                let other_distance = other.distance[w as usize];
+                if other_distance != UNREACHED {
+                    let total = self.depth + 1 + other_distance;
+                    if best.is_none_or(|(shortest, _)| total < shortest) {
+                        best = Some((total, w));
+                    }
+                }
+            }
+        }
+        self.frontier = next;
+        self.depth += 1;
+
+        best.map(|(_, meeting)| meeting)
+    }
+}
+
+/// Rebuilds the ladder `bfs` finds from the distances both sides have worked
+/// out by the time they meet at `meeting`. Of all the shortest ladders, `bfs`
+/// finds the one whose words come first in dictionary order, comparing them
+/// from the start, so the ladder is built a word at a time from the start by
+/// always taking the first neighbour that is still on a shortest ladder.
+/// Every word as far from the start as the meeting and as far from the goal
+/// is on one; working back from those gives the words on one nearer the
+/// start, and nearer the goal, the distances from the goal say which are.
+fn first_shortest_ladder(
+    graph: Subgraph,
+    start: WordId,
+    forward: &Side,
+    backward: &Side,
+    meeting: WordId,
+) -> Option<Vec<WordId>> {
+    let (middle, rest) = (forward.distance[meeting as usize], backward.distance[meeting as usize]);
+    let mut on_ladder = BitSet::new(graph.len());
+    let mut layer: Vec<WordId> = (0..graph.len() as WordId)
+        .filter(|&v| forward.distance[v as usize] == middle && backward.distance[v as usize] == rest)
+        .collect();
+    for &v in layer.iter() {
+        on_ladder.insert(v);
+    }
+    for distance in (0..middle).rev() {
+        let mut next = Vec::new();
+        for &v in layer.iter() {
+            for u in graph.neighbors(v) {
+                if forward.distance[u as usize] == distance && on_ladder.insert(u) {
+                    next.push(u);
+                }
+            }
+        }
+        layer = next;
+    }
+
+    let length = middle + rest;
+    let mut path = vec![start];
+    for i in 1..=length {
+        let on_shortest = |w: WordId| match i <= middle {
+            true => forward.distance[w as usize] == i && on_ladder.contains(w),
+            false => backward.distance[w as usize] == length - i,
+        };
+        path.push(graph.neighbors(path[path.len() - 1]).find(|&w| on_shortest(w))?);
+    }
+
+    Some(path)
+}
+
+/// A breadth-first search run from both ends at once. Each round expands a
+/// whole layer of whichever side has the smaller frontier, so the search
+/// grows two small balls around the start and the goal instead of one big
+/// ball around the start, which explores far fewer words on large graphs.
+/// As soon as a layer reaches a word the other side has already seen, the
+/// length of the shortest ladder is known, and the very ladder `bfs` would
+/// have found is rebuilt from the distances of the two sides.
+pub(crate) fn bidirectional_bfs(
+    graph: Subgraph,
+    start: WordId,
+    goal: WordId,
+    stats: &mut SearchStats,
+) -> Option<Vec<WordId>> {
+    if start == goal {
+        return Some(vec![start])
+    }
+
+    let mut forward = Side::new(start, graph.len());
+    let mut backward = Side::new(goal, graph.len());
+    while !forward.frontier.is_empty() && !backward.frontier.is_empty() {
+        let meeting = if forward.frontier.len() <= backward.frontier.len() {
+            forward.expand(graph, &backward, stats)
+        } else {
+            backward.expand(graph, &forward, stats)
+        };
+
+        if let Some(meeting) = meeting {
+            return first_shortest_ladder(graph, start, &forward, &backward, meeting)
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dict::DICT;
+    use crate::{MoveKind, MoveSet, SolveError, Strategy, WordGraph};
+
+    /// Asserts that every strategy finds a ladder of the same length as
+    /// breadth-first search between each pair of words, or fails the same way,
+    /// and that the bidirectional search finds the very same ladder
+    fn assert_strategies_agree(graph: &WordGraph, pairs: &[(&str, &str)]) {
+        for &(start, goal) in pairs {
+            let expected = graph.shortest_path_with(start, goal, Strategy::Bfs).map(|ladder| ladder.steps());
+            for strategy in Strategy::ALL {
+                let steps = graph.shortest_path_with(start, goal, strategy).map(|ladder| ladder.steps());
+                assert_eq!(steps, expected, "{} from {} to {}", strategy, start, goal);
+            }
+            // the two breadth-first searches agree on the ladder itself, not just its length
+            let words =
+                |strategy| graph.shortest_path_with(start, goal, strategy).map(|ladder| ladder.words().to_vec());
+            assert_eq!(words(Strategy::Bidirectional), words(Strategy::Bfs), "ladder from {} to {}", start, goal);
+        }
+    }
+
+    #[test]
+    fn strategies_agree_on_the_builtin_dictionary() {
+        let graph = WordGraph::from_dict(&DICT);
+        let mut pairs: Vec<(&str, &str)> = DICT
+            .iter()
+            .step_by(37)
+            .zip(DICT.iter().skip(1000).chain(DICT.iter()).step_by(53))
+            .map(|(&a, &b)| (a, b))
+            .collect();
+        pairs.extend([("word", "word"), ("abri", "word"), ("word", "abri"), ("cold", "warm")]);
+        assert_strategies_agree(&graph, &pairs);
+
+        assert_eq!(graph.shortest_path_with("word", "word", Strategy::Bidirectional).unwrap().steps(), 0);
+        let ladder = graph.shortest_path_with("aahs", "foam", Strategy::Bidirectional).unwrap();
+        assert_eq!(ladder.words(), ["aahs", "aals", "ails", "airs", "firs", "firm", "form", "foam"]);
+        let apart = graph.shortest_path_with("abri", "word", Strategy::Bidirectional);
+        assert!(matches!(apart, Err(SolveError::NoPathExists { .. })));
+    }
+
+    #[test]
+    fn strategies_agree_across_lengths() {
+        let words = [
+            "a", "art", "arts", "at", "car", "card", "care", "cart", "cat", "form", "from", "rat", "rats", "scar",
+            "scare", "star", "start", "tar", "tart", "zzz",
+        ];
+        let moves = MoveSet::new(&[MoveKind::Substitute, MoveKind::InsertDelete, MoveKind::Swap]);
+        let graph = WordGraph::from_dict_with_moves(&words, moves);
+        let pairs: Vec<(&str, &str)> = words.iter().flat_map(|&a| words.iter().map(move |&b| (a, b))).collect();
+        assert_strategies_agree(&graph, &pairs);
+    }
+}
@@ -0,0 +1,120 @@
+use std::fmt;
+use std::ops::AddAssign;
+
+/// Each limb holds nine decimal digits, which keeps addition simple and makes
+/// printing the number in decimal a matter of padding each limb
+const LIMB_BASE: u32 = 1_000_000_000;
+
+/// An arbitrarily large non-negative integer, used to count ladders. The
+/// number of shortest ladders can grow exponentially with their length, so
+/// even a `u128` can overflow on large dictionaries. Only what counting needs
+/// is implemented: addition and printing.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct BigUint {
+    /// Base 10⁹ limbs, least significant first, with no trailing zero limbs,
+    /// so zero is the empty vector and needs no allocation
+    limbs: Vec<u32>,
+}
+
+impl BigUint {
+    /// Zero
+    pub fn zero() -> Self {
+        BigUint { limbs: Vec::new() }
+    }
+
+    /// One
+    pub fn one() -> Self {
+        BigUint { limbs: vec![1] }
+    }
+
+    /// Returns whether the number is zero
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    /// The number as a `u64`, if it fits in one
+    pub fn to_u64(&self) -> Option<u64> {
+        self.limbs.iter().rev().try_fold(0u64, |acc, &limb| {
+            acc.checked_mul(LIMB_BASE as u64)?.checked_add(limb as u64)
+        })
+    }
+}
+
+impl From<u64> for BigUint {
+    fn from(mut n: u64) -> Self {
+        let mut limbs = Vec::new();
+        while n > 0 {
+            limbs.push((n % LIMB_BASE as u64) as u32);
+            n /= LIMB_BASE as u64;
+        }
+        BigUint { limbs }
+    }
+}
+
+impl AddAssign<&BigUint> for BigUint {
+    fn add_assign(&mut self, other: &BigUint) {
+        if self.limbs.len() < other.limbs.len() {
+            self.limbs.resize(other.limbs.len(), 0);
+        }
+        let mut carry = 0;
+        for (i, limb) in self.limbs.iter_mut().enumerate() {
+            let sum = *limb + other.limbs.get(i).copied().unwrap_or(0) + carry;
+            *limb = sum % LIMB_BASE;
+            carry = sum / LIMB_BASE;
+            if carry == 0 && i >= other.limbs.len() {
+                break
+            }
+        }
+        if carry > 0 {
+            self.limbs.push(carry);
+        }
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some((most, rest)) = self.limbs.split_last() else {
+            return f.pad_integral(true, "", "0")
+        };
+        let mut digits = most.to_string();
+        for limb in rest.iter().rev() {
+            digits.push_str(&format!("{:09}", limb));
+        }
+        f.pad_integral(true, "", &digits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sum(a: u64, b: u64) -> BigUint {
+        let mut sum = BigUint::from(a);
+        sum += &BigUint::from(b);
+        sum
+    }
+
+    #[test]
+    fn addition_carries_into_a_new_limb() {
+        assert_eq!(sum(999_999_999, 1).to_string(), "1000000000");
+        assert_eq!(sum(999_999_999_999_999_999, 1).to_string(), "1000000000000000000");
+        assert_eq!(sum(u64::MAX, u64::MAX).to_string(), "36893488147419103230");
+        assert_eq!(sum(u64::MAX, u64::MAX).to_u64(), None);
+    }
+
+    #[test]
+    fn addition_stops_once_nothing_is_carried() {
+        assert_eq!(sum(1_000_000_000_000_000_005, 1).to_string(), "1000000000000000006");
+        assert_eq!(sum(1, 1_000_000_000_000_000_005).to_string(), "1000000000000000006");
+        assert_eq!(sum(0, 0), BigUint::zero());
+        assert_eq!(sum(7, 0).to_u64(), Some(7));
+    }
+
+    #[test]
+    fn display_pads_every_limb_but_the_first() {
+        assert_eq!(BigUint::zero().to_string(), "0");
+        assert_eq!(BigUint::from(1_000_000_007).to_string(), "1000000007");
+        assert_eq!(BigUint::from(5_000_000_000_000_000_000).to_string(), "5000000000000000000");
+        assert_eq!(format!("{:>12}", BigUint::from(42)), "          42");
+    }
+}
@@ -0,0 +1,43 @@
+/// A fixed-size set of word ids, one bit per word in the graph. Marking a
+/// word as explored is a single bit operation with no hashing involved.
+#[derive(Clone)]
+pub(crate) struct BitSet {
+    blocks: Vec<u64>,
+}
+
+impl BitSet {
+    /// Makes an empty set that can hold the ids `0..len`
+    pub(crate) fn new(len: usize) -> Self {
+        BitSet { blocks: vec![0; len.div_ceil(64)] }
+    }
+
+    /// Adds `id` to the set, returning whether it was newly added
+    pub(crate) fn insert(&mut self, id: u32) -> bool {
+        let (block, bit) = (id as usize / 64, id % 64);
+        let was_set = self.blocks[block] & (1 << bit) != 0;
+        self.blocks[block] |= 1 << bit;
+        !was_set
+    }
+
+    /// Takes `id` out of the set
+    pub(crate) fn remove(&mut self, id: u32) {
+        self.blocks[id as usize / 64] &= !(1 << (id % 64));
+    }
+
+    /// Returns whether the set holds no ids at all
+    pub(crate) fn is_empty(&self) -> bool {
+        self.blocks.iter().all(|&block| block == 0)
+    }
+
+    /// Adds every id in `other` to the set
+    pub(crate) fn union_with(&mut self, other: &BitSet) {
+        for (block, other_block) in self.blocks.iter_mut().zip(other.blocks.iter()) {
+            *block |= other_block;
+        }
+    }
+
+    /// Returns whether `id` is in the set
+    pub(crate) fn contains(&self, id: u32) -> bool {
+        self.blocks[id as usize / 64] & (1 << (id % 64)) != 0
+    }
+}
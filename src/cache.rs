@@ -0,0 +1,337 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process;
+
+use crate::graph::{build_graph_from_dict, unique_words, Graph, WordId};
+use crate::moves::{MoveKind, MoveSet};
+use crate::precomputed::precomputed_graph;
+
+/// Marks the start of every cache file
+const MAGIC: &[u8; 4] = b"WVSG";
+
+/// The version of the cache format. It has to be bumped whenever the layout
+/// of the files or the graph built from a dictionary changes, so that files
+/// written by older versions are built again rather than misread.
+const CACHE_VERSION: u32 = 1;
+
+/// The most graphs kept in a cache directory. The oldest ones are removed
+/// once there are more, so that a cache used with many dictionaries doesn't
+/// grow without bound.
+const CACHE_LIMIT: usize = 16;
+
+/// The size of the fixed part of a cache file: the magic bytes, the version,
+/// the moves, the dictionary hash and the numbers of words and edges
+const HEADER_LEN: usize = 4 + 4 + 1 + 8 + 4 + 4;
+
+/// A 64-bit FNV-1a hash of the words, in order, each followed by a newline.
+/// Unlike the standard library's hasher it is the same on every platform
+/// and in every release, so it can name files that outlive the binary.
+fn dictionary_hash(words: &[&str]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for word in words {
+        for &byte in word.as_bytes().iter().chain(b"\n") {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+
+    hash
+}
+
+/// Writes a graph out in the cache format: the header, then the offsets,
+/// targets and kinds of its edges, all little-endian. The words themselves
+/// aren't stored, since the graph can only be read back with the dictionary
+/// it was built from.
+fn encode(graph: &Graph, hash: u64) -> Vec<u8> {
+    let (offsets, targets, kinds) = graph.edges();
+    let mut bytes = Vec::with_capacity(HEADER_LEN + 4 * (offsets.len() + targets.len()) + kinds.len());
+    bytes.extend_from_slice(MAGIC);
+    bytes.extend_from_slice(&CACHE_VERSION.to_le_bytes());
+    bytes.push(graph.moves().bits());
+    bytes.extend_from_slice(&hash.to_le_bytes());
+    bytes.extend_from_slice(&(graph.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(&(targets.len() as u32).to_le_bytes());
+    for &offset in offsets {
+        bytes.extend_from_slice(&offset.to_le_bytes());
+    }
+    for &target in targets {
+        bytes.extend_from_slice(&target.to_le_bytes());
+    }
+    bytes.extend(kinds.iter().map(|&kind| kind as u8));
+
+    bytes
+}
+
+/// Reads little-endian numbers off the front of a cache file
+struct Reader<'b> {
+    bytes: &'b [u8],
+}
+
+impl<'b> Reader<'b> {
+    fn take(&mut self, n: usize) -> Option<&'b [u8]> {
+        if self.bytes.len() < n {
+            return None
+        }
+        let (taken, rest) = self.bytes.split_at(n);
+        self.bytes = rest;
+        Some(taken)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        Some(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        Some(u32::from_le_bytes(self.take(4)?.try_into().ok()?))
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        Some(u64::from_le_bytes(self.take(8)?.try_into().ok()?))
+    }
+}
+
+/// The kind of move written to the cache as `byte`, the reverse of `kind as u8`
+fn move_kind(byte: u8) -> Option<MoveKind> {
+    match byte {
+        0 => Some(MoveKind::Substitute),
+        1 => Some(MoveKind::InsertDelete),
+        2 => Some(MoveKind::Swap),
+        3 => Some(MoveKind::Anagram),
+        _ => None,
+    }
+}
+
+/// Reads a graph back from the cache format, for the given words and moves.
+/// Anything that doesn't match, from a file of another version or for other
+/// words to one that was cut short, gives `None` rather than a broken graph.
+fn decode<'a>(bytes: &[u8], words: Vec<&'a str>, moves: MoveSet, hash: u64) -> Option<Graph<'a>> {
+    let mut reader = Reader { bytes };
+    if reader.take(4)? != MAGIC || reader.u32()? != CACHE_VERSION {
+        return None
+    }
+    if MoveSet::from_bits(reader.u8()?)? != moves || reader.u64()? != hash || reader.u32()? as usize != words.len() {
+        return None
+    }
+    let edges = reader.u32()? as usize;
+    let offsets: Vec<u32> = (0..=words.len()).map(|_| reader.u32()).collect::<Option<_>>()?;
+    let targets: Vec<WordId> = (0..edges).map(|_| reader.u32()).collect::<Option<_>>()?;
+    let kinds: Vec<MoveKind> = reader.take(edges)?.iter().map(|&kind| move_kind(kind)).collect::<Option<_>>()?;
+    let in_order = offsets.windows(2).all(|pair| pair[0] <= pair[1]);
+    if !reader.bytes.is_empty() || !in_order || offsets[0] != 0 || offsets[words.len()] as usize != edges {
+        return None
+    }
+    if targets.iter().any(|&target| target as usize >= words.len()) {
+        return None
+    }
+
+    Some(Graph::from_parts(words, offsets, targets, kinds, moves))
+}
+
+/// A directory of prebuilt word graphs. Building the graph of a large
+/// dictionary takes far longer than reading it back, so a graph is saved the
+/// first time it is built, and later loaded instead of being built again.
+/// Each file is named after a hash of the words the graph was built from and
+/// the moves it was built with, so a dictionary that changes in any way gets
+/// a graph of its own, and the stale one is eventually removed. Files are read
+/// in whole rather than memory-mapped, which needs no unsafe code and is
+/// still much faster than building the graph.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GraphCache {
+    dir: PathBuf,
+}
+
+impl GraphCache {
+    /// A cache kept in `dir`, which is created when the first graph is saved
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        GraphCache { dir: dir.into() }
+    }
+
+    /// A cache in the user's cache directory: `$XDG_CACHE_HOME/weavesolve`
+    /// or `~/.cache/weavesolve` on Unix, `~/Library/Caches/weavesolve` on
+    /// macOS and `%LOCALAPPDATA%\weavesolve` on Windows. Returns `None` if
+    /// the environment doesn't say where that is.
+    pub fn user() -> Option<Self> {
+        let base = if cfg!(windows) {
+            PathBuf::from(env::var_os("LOCALAPPDATA")?)
+        } else if cfg!(target_os = "macos") {
+            PathBuf::from(env::var_os("HOME")?).join("Library").join("Caches")
+        } else {
+            match env::var_os("XDG_CACHE_HOME").filter(|dir| !dir.is_empty()) {
+                Some(dir) => PathBuf::from(dir),
+                None => PathBuf::from(env::var_os("HOME")?).join(".cache"),
+            }
+        };
+
+        Some(GraphCache::new(base.join("weavesolve")))
+    }
+
+    /// The directory the graphs are kept in
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// The file a graph of the words with hash `hash` and the given moves is
+    /// kept in
+    fn path(&self, hash: u64, moves: MoveSet) -> PathBuf {
+        self.dir.join(format!("graph-{:016x}-{:x}.bin", hash, moves.bits()))
+    }
+
+    /// Loads the graph of `dict` with the given moves if it has been saved,
+    /// and otherwise builds it and saves it for next time. The cache only
+    /// ever saves time: a graph that can't be read is built again, and one
+    /// that can't be saved is simply not saved. The graph of the built-in
+    /// dictionary with the default moves is never saved, since it is
+    /// precomputed, and neither is a graph with no words, which takes no time
+    /// to build.
+    pub(crate) fn load_or_build<'a>(&self, dict: &[&'a str], moves: MoveSet) -> Graph<'a> {
+        if let Some(graph) = precomputed_graph(dict, moves) {
+            return graph
+        }
+        let words = unique_words(dict);
+        if words.is_empty() {
+            return build_graph_from_dict(dict, moves)
+        }
+        let hash = dictionary_hash(&words);
+        let path = self.path(hash, moves);
+        if let Some(graph) = fs::read(&path).ok().and_then(|bytes| decode(&bytes, words, moves, hash)) {
+            return graph
+        }
+
+        let graph = build_graph_from_dict(dict, moves);
+        if self.save(&path, &encode(&graph, hash)).is_ok() {
+            // a failure to tidy up does no harm beyond leaving old files around
+            let _ = self.prune();
+        }
+        graph
+    }
+
+    /// Writes a cache file in full under a temporary name first, so that a
+    /// run that reads it at the same time never sees half of it
+    fn save(&self, path: &Path, bytes: &[u8]) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let partial = path.with_extension(format!("{}.tmp", process::id()));
+        fs::write(&partial, bytes)?;
+        fs::rename(&partial, path).inspect_err(|_| {
+            let _ = fs::remove_file(&partial);
+        })
+    }
+
+    /// Removes the least recently written graphs beyond [`CACHE_LIMIT`]
+    fn prune(&self) -> io::Result<()> {
+        let mut graphs = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let entry = entry?;
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            if name.starts_with("graph-") && name.ends_with(".bin") {
+                graphs.push((entry.metadata()?.modified()?, entry.path()));
+            }
+        }
+        graphs.sort_unstable_by(|a, b| b.cmp(a));
+        for (_, path) in graphs.into_iter().skip(CACHE_LIMIT) {
+            fs::remove_file(path)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use super::*;
+
+    const WORDS: [&str; 8] = ["card", "cold", "cord", "dork", "ward", "warm", "word", "work"];
+
+    /// An empty cache in a directory of its own, so tests can run in parallel
+    fn scratch_cache(name: &str) -> GraphCache {
+        let dir = env::temp_dir().join(format!("weavesolve-{}-{}", name, process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        GraphCache::new(dir)
+    }
+
+    /// The file the graph of `WORDS` with the default moves is kept in
+    fn words_path(cache: &GraphCache) -> PathBuf {
+        cache.path(dictionary_hash(&WORDS), MoveSet::default())
+    }
+
+    #[test]
+    fn round_trip_loads_an_equal_graph() {
+        let cache = scratch_cache("round-trip");
+        let built = build_graph_from_dict(&WORDS, MoveSet::default());
+        assert_eq!(cache.load_or_build(&WORDS, MoveSet::default()), built);
+
+        let bytes = fs::read(words_path(&cache)).unwrap();
+        let hash = dictionary_hash(&WORDS);
+        assert_eq!(decode(&bytes, WORDS.to_vec(), MoveSet::default(), hash), Some(built.clone()));
+        assert_eq!(cache.load_or_build(&WORDS, MoveSet::default()), built);
+        fs::remove_dir_all(cache.dir()).unwrap();
+    }
+
+    #[test]
+    fn damaged_files_are_built_again() {
+        let cache = scratch_cache("damaged");
+        let built = build_graph_from_dict(&WORDS, MoveSet::default());
+        let good = encode(&built, dictionary_hash(&WORDS));
+        let mut bad_magic = good.clone();
+        bad_magic[0] = b'X';
+        let mut wrong_version = good.clone();
+        wrong_version[4..8].copy_from_slice(&(CACHE_VERSION + 1).to_le_bytes());
+        let truncated = good[..good.len() - 3].to_vec();
+
+        for damaged in [truncated, bad_magic, wrong_version] {
+            let hash = dictionary_hash(&WORDS);
+            assert_eq!(decode(&damaged, WORDS.to_vec(), MoveSet::default(), hash), None);
+            fs::create_dir_all(cache.dir()).unwrap();
+            fs::write(words_path(&cache), &damaged).unwrap();
+            assert_eq!(cache.load_or_build(&WORDS, MoveSet::default()), built);
+            assert_eq!(fs::read(words_path(&cache)).unwrap(), good);
+        }
+        fs::remove_dir_all(cache.dir()).unwrap();
+    }
+
+    #[test]
+    fn empty_graphs_are_not_saved() {
+        let cache = scratch_cache("empty");
+        assert_eq!(cache.load_or_build(&[], MoveSet::default()), build_graph_from_dict(&[], MoveSet::default()));
+        assert!(!cache.dir().exists());
+    }
+
+    #[test]
+    fn changed_dictionary_gets_another_file() {
+        let cache = scratch_cache("changed");
+        let mut changed = WORDS.to_vec();
+        changed.push("worm");
+        cache.load_or_build(&WORDS, MoveSet::default());
+        let graph = cache.load_or_build(&changed, MoveSet::default());
+
+        assert_eq!(graph, build_graph_from_dict(&changed, MoveSet::default()));
+        assert_ne!(words_path(&cache), cache.path(dictionary_hash(&changed), MoveSet::default()));
+        assert_eq!(fs::read_dir(cache.dir()).unwrap().count(), 2);
+        fs::remove_dir_all(cache.dir()).unwrap();
+    }
+
+    #[test]
+    fn prune_keeps_the_newest_graphs() {
+        let cache = scratch_cache("prune");
+        fs::create_dir_all(cache.dir()).unwrap();
+        let epoch = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+        for i in 0..CACHE_LIMIT + 3 {
+            let file = fs::File::create(cache.dir().join(format!("graph-{:016x}-1.bin", i))).unwrap();
+            file.set_modified(epoch + Duration::from_secs(i as u64)).unwrap();
+        }
+        fs::write(cache.dir().join("notes.txt"), "kept").unwrap();
+        cache.prune().unwrap();
+
+        let mut left: Vec<String> =
+            fs::read_dir(cache.dir()).unwrap().map(|entry| entry.unwrap().file_name().into_string().unwrap()).collect();
+        left.sort();
+        let mut expected: Vec<String> = (3..CACHE_LIMIT + 3).map(|i| format!("graph-{:016x}-1.bin", i)).collect();
+        expected.push("notes.txt".to_string());
+        assert_eq!(left, expected);
+        fs::remove_dir_all(cache.dir()).unwrap();
+    }
+}
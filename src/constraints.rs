@@ -0,0 +1,100 @@
+use std::collections::HashSet;
+
+use crate::bitset::BitSet;
+use crate::graph::{Graph, WordId};
+use crate::tiers::{Tier, TieredDictionary};
+
+/// Words and letters that a ladder must not use, and words that it may only
+/// start or end on. Constraints are applied to a
+/// graph that has already been built, with [`WordGraph::constrained`], so the
+/// same graph can be searched with different exclusions for every query.
+///
+/// [`WordGraph::constrained`]: crate::WordGraph::constrained
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Constraints {
+    words: HashSet<String>,
+    letters: HashSet<char>,
+    endpoints_only: HashSet<String>,
+}
+
+impl Constraints {
+    /// No constraints at all
+    pub fn new() -> Self {
+        Constraints::default()
+    }
+
+    /// Excludes a single word from the ladder. Words are matched after
+    /// lowercasing, like the words of a dictionary.
+    pub fn exclude_word(&mut self, word: &str) -> &mut Self {
+        self.words.insert(word.trim().to_lowercase());
+        self
+    }
+
+    /// Excludes every word in `words` from the ladder
+    pub fn exclude_words<I, S>(&mut self, words: I) -> &mut Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        for word in words {
+            self.exclude_word(word.as_ref());
+        }
+        self
+    }
+
+    /// Bans every letter in `letters`, excluding all words that contain any
+    /// of them
+    pub fn ban_letters(&mut self, letters: &str) -> &mut Self {
+        self.letters.extend(letters.chars().flat_map(char::to_lowercase));
+        self
+    }
+
+    /// Keeps every word in a less common tier than `tier` out of the middle of
+    /// the ladder. Unlike excluded words, these can still be the start or the
+    /// goal, or a word the ladder is asked to visit.
+    pub fn restrict_tier(&mut self, tiers: &TieredDictionary, tier: Tier) -> &mut Self {
+        self.endpoints_only.extend(tiers.words_beyond(tier).into_iter().map(String::from));
+        self
+    }
+
+    /// Returns whether `word` may be used on a ladder
+    pub fn allows(&self, word: &str) -> bool {
+        !self.words.contains(word) && !word.chars().any(|c| self.letters.contains(&c))
+    }
+
+    /// Returns whether there is nothing to exclude
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty() && self.letters.is_empty() && self.endpoints_only.is_empty()
+    }
+
+    /// The set of words in `graph` that these constraints rule out
+    pub(crate) fn removed_words(&self, graph: &Graph) -> BitSet {
+        let mut removed = BitSet::new(graph.len());
+        if self.words.is_empty() && self.letters.is_empty() {
+            return removed
+        }
+        for id in 0..graph.len() as WordId {
+            if !self.allows(graph.word(id)) {
+                removed.insert(id);
+            }
+        }
+
+        removed
+    }
+
+    /// The set of words in `graph` that may only be used at the ends of the
+    /// ladder, or `None` if there are no such words
+    pub(crate) fn endpoint_only_words(&self, graph: &Graph) -> Option<BitSet> {
+        if self.endpoints_only.is_empty() {
+            return None
+        }
+        let mut endpoints_only = BitSet::new(graph.len());
+        for word in self.endpoints_only.iter() {
+            if let Some(id) = graph.id(word) {
+                endpoints_only.insert(id);
+            }
+        }
+
+        Some(endpoints_only)
+    }
+}
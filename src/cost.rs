@@ -0,0 +1,188 @@
+use crate::ladder::Ladder;
+use crate::moves::MoveKind;
+
+/// A single move along a ladder, as seen by a [`CostModel`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Move<'a> {
+    /// The word the move starts from
+    pub from: &'a str,
+    /// The word the move leads to
+    pub to: &'a str,
+    /// The kind of move it is
+    pub kind: MoveKind,
+    /// The position of the first letter that changes, or that is inserted or
+    /// deleted
+    pub position: usize,
+    /// The position of the letter changed by the move before this one, or
+    /// `None` for the first move of the ladder
+    pub previous: Option<usize>,
+}
+
+impl Move<'_> {
+    /// The letter the move replaces or deletes, or `None` if it inserts one.
+    /// For a swap or an anagram, this is the first letter that moves.
+    pub fn old_letter(&self) -> Option<char> {
+        if self.from.len() < self.to.len() {
+            return None
+        }
+        self.from[self.position..].chars().next()
+    }
+
+    /// The letter the move puts in place of the old one or inserts, or
+    /// `None` if it deletes one. For a swap or an anagram, this is the letter
+    /// that takes the place of the first letter that moves.
+    pub fn new_letter(&self) -> Option<char> {
+        if self.to.len() < self.from.len() {
+            return None
+        }
+        self.to[self.position..].chars().next()
+    }
+}
+
+/// Decides how much each move costs, so that the cheapest ladder can be
+/// found with [`WordGraph::cheapest_path`] rather than the shortest one. A
+/// move's cost may depend on the move before it, but never on anything
+/// earlier.
+///
+/// [`WordGraph::cheapest_path`]: crate::WordGraph::cheapest_path
+pub trait CostModel {
+    /// The cost of making `step`
+    fn cost(&self, step: &Move) -> u32;
+}
+
+/// The cost model used by the command line: every move costs `base`, with
+/// `vowel` added when the move changes, inserts or deletes a vowel or changes
+/// a letter into one, and `repeat` added when the move changes the same
+/// position as the move before it. The default charges one for every move,
+/// which makes the cheapest ladders the shortest ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WeightedMoves {
+    /// The cost of every move
+    pub base: u32,
+    /// The extra cost of a move that changes, inserts or deletes a vowel or
+    /// changes a letter into one
+    pub vowel: u32,
+    /// The extra cost of changing the same position twice in a row
+    pub repeat: u32,
+}
+
+impl Default for WeightedMoves {
+    fn default() -> Self {
+        WeightedMoves { base: 1, vowel: 0, repeat: 0 }
+    }
+}
+
+/// The letters that count as vowels for [`WeightedMoves`]
+fn is_vowel(c: char) -> bool {
+    matches!(c, 'a' | 'e' | 'i' | 'o' | 'u')
+}
+
+impl CostModel for WeightedMoves {
+    fn cost(&self, step: &Move) -> u32 {
+        let mut cost = self.base;
+        if step.old_letter().into_iter().chain(step.new_letter()).any(is_vowel) {
+            cost += self.vowel;
+        }
+        if step.previous == Some(step.position) {
+            cost += self.repeat;
+        }
+
+        cost
+    }
+}
+
+/// The position of the first letter at which two words differ, which for
+/// two words one move apart is the letter the move changes, inserts or
+/// deletes
+pub(crate) fn changed_position(from: &str, to: &str) -> usize {
+    from.bytes().zip(to.bytes()).position(|(a, b)| a != b).unwrap_or(from.len().min(to.len()))
+}
+
+/// A ladder with the lowest total cost under some [`CostModel`], along with
+/// what each of its moves cost
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheapestLadder<'a> {
+    ladder: Ladder<'a>,
+    costs: Vec<u32>,
+}
+
+impl<'a> CheapestLadder<'a> {
+    pub(crate) fn new(ladder: Ladder<'a>, costs: Vec<u32>) -> Self {
+        CheapestLadder { ladder, costs }
+    }
+
+    /// The ladder itself
+    pub fn ladder(&self) -> &Ladder<'a> {
+        &self.ladder
+    }
+
+    /// The cost of each move of the ladder, in order
+    pub fn costs(&self) -> &[u32] {
+        &self.costs
+    }
+
+    /// The total cost of the ladder
+    pub fn cost(&self) -> u64 {
+        self.costs.iter().map(|&cost| cost as u64).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dict::DICT;
+    use crate::{MoveSet, Strategy, WordGraph};
+
+    #[test]
+    fn the_default_cost_is_the_shortest_length() {
+        let graph = WordGraph::from_dict(&DICT);
+        let model = WeightedMoves::default();
+        for (&start, &goal) in DICT.iter().step_by(211).zip(DICT.iter().skip(700).step_by(197)) {
+            let shortest = graph.shortest_path_with(start, goal, Strategy::Bfs).map(|ladder| ladder.steps() as u64);
+            let cheapest = graph.cheapest_path(start, goal, &model).map(|ladder| ladder.cost());
+            assert_eq!(cheapest, shortest, "from {} to {}", start, goal);
+        }
+    }
+
+    #[test]
+    fn vowel_costs_steer_the_ladder_around_vowels() {
+        // both ladders take six moves, but the first changes a vowel three
+        // times and the second only twice
+        let graph = WordGraph::from_dict(&DICT);
+        let plain = graph.cheapest_path("dewy", "fuci", &WeightedMoves::default()).unwrap();
+        assert_eq!(plain.ladder().words(), ["dewy", "deny", "dene", "dune", "duce", "duci", "fuci"]);
+
+        let model = WeightedMoves { vowel: 5, ..WeightedMoves::default() };
+        let cheapest = graph.cheapest_path("dewy", "fuci", &model).unwrap();
+        assert_eq!(cheapest.ladder().words(), ["dewy", "deny", "dent", "dunt", "duct", "duci", "fuci"]);
+        assert_eq!(cheapest.costs(), [1, 1, 6, 1, 6, 1]);
+        assert_eq!(cheapest.cost(), cheapest.costs().iter().map(|&cost| cost as u64).sum::<u64>());
+    }
+
+    #[test]
+    fn repeat_costs_steer_the_ladder_off_the_same_position() {
+        // ab -> acb -> adcb inserts at the same position twice in a row,
+        // while the longer way round never changes one position twice
+        let words = ["ab", "acb", "adcb", "eb", "edb", "edcb"];
+        let moves = MoveSet::new(&[MoveKind::Substitute, MoveKind::InsertDelete]);
+        let graph = WordGraph::from_dict_with_moves(&words, moves);
+        let plain = graph.cheapest_path("ab", "adcb", &WeightedMoves::default()).unwrap();
+        assert_eq!(plain.ladder().words(), ["ab", "acb", "adcb"]);
+
+        let model = WeightedMoves { repeat: 3, ..WeightedMoves::default() };
+        let cheapest = graph.cheapest_path("ab", "adcb", &model).unwrap();
+        assert_eq!(cheapest.ladder().words(), ["ab", "eb", "edb", "edcb", "adcb"]);
+        assert_eq!(cheapest.costs(), [1, 1, 1, 1]);
+        assert_eq!(cheapest.cost(), 4);
+    }
+
+    #[test]
+    fn the_readme_example_costs_nine() {
+        let graph = WordGraph::from_dict(&DICT);
+        let model = WeightedMoves { vowel: 5, repeat: 3, ..WeightedMoves::default() };
+        let cheapest = graph.cheapest_path("cold", "warm", &model).unwrap();
+        assert_eq!(cheapest.ladder().words(), ["cold", "cord", "corm", "worm", "warm"]);
+        assert_eq!(cheapest.costs(), [1, 1, 1, 6]);
+        assert_eq!(cheapest.cost(), 9);
+    }
+}
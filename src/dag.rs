@@ -0,0 +1,161 @@
+use std::collections::HashMap;
+
+use crate::graph::{Graph, Subgraph, WordId};
+use crate::ladder::Ladder;
+use crate::search::{SearchStats, UNREACHED};
+
+/// Every shortest ladder between two words, stored as the directed acyclic
+/// graph of the moves that appear on at least one of them. A word can be
+/// reached from several words of the layer before it, so unlike the parent
+/// map of `bfs` each word may have many parents, and the number of ladders
+/// can grow exponentially in their length while the graph stays small.
+#[derive(Debug, Clone)]
+pub(crate) struct Dag {
+    pub(crate) start: WordId,
+    pub(crate) goal: WordId,
+    pub(crate) steps: usize,
+    /// The moves from each word to the words one step closer to the goal,
+    /// in dictionary order
+    pub(crate) children: HashMap<WordId, Vec<WordId>>,
+    pub(crate) stats: SearchStats,
+}
+
+impl Dag {
+    /// The words one step closer to the goal than `v` on some shortest ladder
+    pub(crate) fn children(&self, v: WordId) -> &[WordId] {
+        self.children.get(&v).map(Vec::as_slice).unwrap_or(&[])
+    }
+}
+
+/// A breadth-first search that records every parent a word is reached from in
+/// the layer before it, rather than only the first. The search stops once the
+/// layer containing the goal is complete, then walks back from the goal to keep
+/// only the words and moves that actually lie on a shortest ladder.
+pub(crate) fn shortest_path_dag(graph: Subgraph, start: WordId, goal: WordId) -> Option<Dag> {
+    let mut stats = SearchStats::default();
+    let mut distance = vec![UNREACHED; graph.len()];
+    let mut parents: HashMap<WordId, Vec<WordId>> = HashMap::new();
+    let mut frontier = vec![start];
+    let mut depth = 0;
+    distance[start as usize] = 0;
+    while distance[goal as usize] == UNREACHED {
+        if frontier.is_empty() {
+            return None
+        }
+
+        let mut next = Vec::new();
+        for &v in frontier.iter() {
+            stats.expanded += 1;
+            for w in graph.neighbors(v) {
+                if distance[w as usize] == UNREACHED {
+                    distance[w as usize] = depth + 1;
+                    next.push(w);
+                }
+                if distance[w as usize] == depth + 1 {
+                    parents.entry(w).or_default().push(v);
+                }
+            }
+        }
+        frontier = next;
+        depth += 1;
+    }
+
+    // walk back from the goal, keeping only the moves that lead to it
+    let mut children: HashMap<WordId, Vec<WordId>> = HashMap::new();
+    let mut stack = vec![goal];
+    while let Some(w) = stack.pop() {
+        for &v in parents.get(&w).map(Vec::as_slice).unwrap_or(&[]) {
+            let moves = children.entry(v).or_default();
+            // `v` only needs walking back from the first time we reach it
+            if moves.is_empty() {
+                stack.push(v);
+            }
+            moves.push(w);
+        }
+    }
+    for moves in children.values_mut() {
+        moves.sort_unstable();
+    }
+
+    Some(Dag { start, goal, steps: depth as usize, children, stats })
+}
+
+/// Every shortest ladder between two words. The ladders are not built until
+/// they are asked for: [`ShortestLadders::iter`] walks them one at a time, in
+/// dictionary order, so a cap on how many are wanted can be applied with
+/// [`Iterator::take`] without ever building the rest.
+#[derive(Debug, Clone)]
+pub struct ShortestLadders<'g, 'a> {
+    graph: &'g Graph<'a>,
+    dag: Dag,
+}
+
+impl<'g, 'a> ShortestLadders<'g, 'a> {
+    pub(crate) fn new(graph: &'g Graph<'a>, dag: Dag) -> Self {
+        ShortestLadders { graph, dag }
+    }
+
+    /// The number of single-letter changes on each of the ladders
+    pub fn steps(&self) -> usize {
+        self.dag.steps
+    }
+
+    /// Counters collected by the search that found the ladders
+    pub fn stats(&self) -> SearchStats {
+        self.dag.stats
+    }
+
+    /// Iterates over every shortest ladder, in dictionary order
+    pub fn iter(&self) -> Ladders<'_, 'a> {
+        Ladders { ladders: self, path: vec![self.dag.start], next_child: vec![0], done: false }
+    }
+}
+
+/// The iterator returned by [`ShortestLadders::iter`]. It does a depth-first
+/// walk of the shortest-ladder graph, keeping only the ladder it is currently
+/// on in memory.
+#[derive(Debug, Clone)]
+pub struct Ladders<'l, 'a> {
+    ladders: &'l ShortestLadders<'l, 'a>,
+    path: Vec<WordId>,
+    /// For each word on `path`, the index of the next of its children to try
+    next_child: Vec<usize>,
+    done: bool,
+}
+
+impl<'a> Iterator for Ladders<'_, 'a> {
+    type Item = Ladder<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let dag = &self.ladders.dag;
+        while !self.done {
+            let v = self.path[self.path.len() - 1];
+            if v == dag.goal {
+                let ladder = Ladder::new(self.path.iter().map(|&id| self.ladders.graph.word(id)).collect(), dag.stats);
+                self.backtrack();
+                return Some(ladder)
+            }
+
+            let depth = self.path.len() - 1;
+            match dag.children(v).get(self.next_child[depth]) {
+                Some(&w) => {
+                    self.next_child[depth] += 1;
+                    self.path.push(w);
+                    self.next_child.push(0);
+                }
+                None => self.backtrack(),
+            }
+        }
+
+        None
+    }
+}
+
+impl Ladders<'_, '_> {
+    /// Steps back off the last word of the current ladder
+    fn backtrack(&mut self) {
+        self.path.pop();
+        self.next_child.pop();
+        self.done = self.path.is_empty();
+    }
+}
@@ -1,4 +1,6 @@
-pub const DICT: [&'static str; 4029] = [
+/// The built-in dictionary of 4-letter words used when no other word list
+/// is supplied.
+pub static DICT: [&str; 4029] = [
     "aahs","aals","abas","abba","abbe","abed","abet","able","ably",
     "abos","abri","abut","abye","abys","aced","aces","ache","achy",
     "acid","acme","acne","acre","acta","acts","acyl","adds","adit",
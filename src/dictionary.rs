@@ -0,0 +1,80 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::dict::DICT;
+use crate::validate::is_allowed_char;
+
+/// An owned list of words to build a [`WordGraph`](crate::WordGraph) from.
+/// Words are normalized to lowercase, sorted and deduplicated, so the same
+/// list always produces the same graph no matter how the source was ordered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dictionary {
+    words: Vec<String>,
+}
+
+impl Dictionary {
+    /// The built-in dictionary of 4-letter words
+    pub fn builtin() -> Self {
+        Dictionary::from_words(DICT)
+    }
+
+    /// Builds a dictionary from any list of words. Each word is trimmed and
+    /// lowercased; blank entries and entries containing anything other than
+    /// the letters a-z (apostrophes, hyphens, digits and so on) are skipped,
+    /// since they could never be used as part of a ladder.
+    pub fn from_words<I, S>(words: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut words: Vec<String> = words
+            .into_iter()
+            .map(|word| word.as_ref().trim().to_lowercase())
+            .filter(|word| !word.is_empty() && word.chars().all(is_allowed_char))
+            .collect();
+        words.sort();
+        words.dedup();
+
+        Dictionary { words }
+    }
+
+    /// Parses a newline-separated word list
+    pub fn parse(list: &str) -> Self {
+        Dictionary::from_words(list.lines())
+    }
+
+    /// Reads a newline-separated word list from a file
+    pub fn from_file<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Ok(Dictionary::parse(&fs::read_to_string(path)?))
+    }
+
+    /// Every word in the dictionary, in sorted order
+    pub fn words(&self) -> Vec<&str> {
+        self.words.iter().map(String::as_str).collect()
+    }
+
+    /// The words in the dictionary with exactly `len` letters, in sorted order
+    pub fn bucket(&self, len: usize) -> Vec<&str> {
+        self.words
+            .iter()
+            .filter(|word| word.len() == len)
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Returns whether `word` is in the dictionary
+    pub fn contains(&self, word: &str) -> bool {
+        self.words.binary_search_by(|probe| probe.as_str().cmp(word)).is_ok()
+    }
+
+    /// The number of words in the dictionary
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    /// Returns whether the dictionary has no words in it at all
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+}
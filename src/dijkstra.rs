@@ -0,0 +1,104 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use crate::cost::{changed_position, CostModel, Move};
+use crate::error::SolveError;
+use crate::graph::{Subgraph, WordId};
+use crate::moves::MoveKind;
+use crate::search::{no_path_error, SearchStats};
+use crate::validate::validate;
+
+/// Marks a search state with no parent, which is only the starting state
+const NO_PARENT: usize = usize::MAX;
+
+/// Dijkstra's algorithm for the cheapest ladder under `model`. Since the cost
+/// of a move may depend on the position the move before it changed, the search
+/// runs over states made of a word together with that position (or none, for
+/// the start word) rather than over words alone: reaching a word cheaply is no
+/// use if the cheap way in makes every way out expensive. States are numbered
+/// `word * slots + position + 1`, so all of them fit in flat arrays. The first
+/// time a state of the goal word comes off the heap its cost is the lowest
+/// possible, and the ladder is returned with the cost of each of its moves.
+pub(crate) fn dijkstra(
+    graph: Subgraph,
+    start: WordId,
+    goal: WordId,
+    model: &dyn CostModel,
+    stats: &mut SearchStats,
+) -> Option<(Vec<WordId>, Vec<u32>)> {
+    let slots = (0..graph.len() as WordId).map(|id| graph.word(id).len()).max().unwrap_or(0) + 1;
+    let state = |word: WordId, previous: Option<usize>| word as usize * slots + previous.map_or(0, |p| p + 1);
+
+    let mut best = vec![u64::MAX; graph.len() * slots];
+    let mut parent = vec![NO_PARENT; graph.len() * slots];
+    let mut move_cost = vec![0; graph.len() * slots];
+    let mut open = BinaryHeap::new();
+    best[state(start, None)] = 0;
+    open.push(Reverse((0, state(start, None))));
+    while let Some(Reverse((cost, s))) = open.pop() {
+        // a state can be pushed more than once before it is expanded; only
+        // the cheapest copy counts
+        if cost > best[s] {
+            continue
+        }
+        let v = (s / slots) as WordId;
+        if v == goal {
+            return Some(walk_states(&parent, &move_cost, s, slots))
+        }
+        stats.expanded += 1;
+
+        let previous = (s % slots).checked_sub(1);
+        for w in graph.neighbors(v) {
+            let (from, to) = (graph.word(v), graph.word(w));
+            let position = changed_position(from, to);
+            let kind = graph.move_kind(v, w).unwrap_or(MoveKind::Substitute);
+            let step_cost = model.cost(&Move { from, to, kind, position, previous });
+            let next = state(w, Some(position));
+            let total = cost + step_cost as u64;
+            if total < best[next] {
+                best[next] = total;
+                parent[next] = s;
+                move_cost[next] = step_cost;
+                open.push(Reverse((total, next)));
+            }
+        }
+    }
+
+    None
+}
+
+/// Walks the parent states back from `end` to the starting state, returning
+/// the words along the way in order along with the cost of each move
+fn walk_states(parent: &[usize], move_cost: &[u32], end: usize, slots: usize) -> (Vec<WordId>, Vec<u32>) {
+    let mut path = vec![(end / slots) as WordId];
+    let mut costs = Vec::new();
+    let mut s = end;
+    while parent[s] != NO_PARENT {
+        costs.push(move_cost[s]);
+        s = parent[s];
+        path.push((s / slots) as WordId);
+    }
+    path.reverse();
+    costs.reverse();
+
+    (path, costs)
+}
+
+/// Validates the two words and then finds the cheapest ladder between them
+/// with `dijkstra`
+pub(crate) fn find_cheapest_path<'g>(
+    graph: Subgraph<'_, 'g>,
+    start: &str,
+    end: &str,
+    model: &dyn CostModel,
+) -> Result<(Vec<&'g str>, Vec<u32>, SearchStats), SolveError> {
+    let (start, end) = validate(graph, start, end)?;
+    if !graph.connected(start, end) {
+        return Err(no_path_error(graph, start, end))
+    }
+
+    let mut stats = SearchStats::default();
+    let (path, costs) = dijkstra(graph, start, end, model, &mut stats).ok_or_else(|| no_path_error(graph, start, end))?;
+
+    Ok((path.into_iter().map(|id| graph.word(id)).collect(), costs, stats))
+}
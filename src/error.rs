@@ -0,0 +1,72 @@
+use std::error::Error;
+use std::fmt;
+
+/// Everything that can go wrong while looking for a ladder
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolveError {
+    /// The word is empty or contains characters that no dictionary word can
+    /// contain, such as digits, punctuation or uppercase letters
+    InvalidCharacters(String),
+    /// The start word is not in the dictionary
+    UnknownStartWord(String),
+    /// The goal word is not in the dictionary
+    UnknownGoalWord(String),
+    /// A word the ladder has to visit is not in the dictionary
+    UnknownWaypoint(String),
+    /// A word the ladder has to visit was given more than once, but a ladder
+    /// never visits the same word twice
+    RepeatedWaypoint(String),
+    /// A word the ladder has to visit is in the dictionary, but the caller
+    /// excluded it from the ladder, either directly or by banning one of its
+    /// letters
+    ExcludedWord(String),
+    /// The start and goal words have a different number of letters, so no
+    /// sequence of single-letter changes can connect them
+    LengthMismatch { start: String, goal: String },
+    /// Both words are in the dictionary, but they are in different
+    /// components of the graph
+    NoPathExists { start: String, goal: String },
+    /// There are ladders between two consecutive words the ladder has to
+    /// visit, but all of them go back through a word the ladder already used
+    WaypointBlocked { start: String, goal: String },
+    /// More than one problem was found with the input; each one is listed
+    InvalidInput(Vec<SolveError>),
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SolveError::InvalidCharacters(word) => {
+                write!(f, "{:?} must be made up of lowercase letters a-z only", word)
+            }
+            SolveError::UnknownStartWord(word) => write!(f, "{} is not a valid word!", word),
+            SolveError::UnknownGoalWord(word) => write!(f, "{} is not a valid word!", word),
+            SolveError::UnknownWaypoint(word) => write!(f, "{} is not a valid word!", word),
+            SolveError::RepeatedWaypoint(word) => write!(f, "{} can only be visited once", word),
+            SolveError::ExcludedWord(word) => write!(f, "{} has been excluded from the ladder", word),
+            SolveError::LengthMismatch { start, goal } => write!(
+                f,
+                "{} and {} have different lengths ({} and {} letters)",
+                start,
+                goal,
+                start.chars().count(),
+                goal.chars().count()
+            ),
+            SolveError::NoPathExists { start, goal } => write!(f, "there is no ladder from {} to {}", start, goal),
+            SolveError::WaypointBlocked { start, goal } => write!(
+                f,
+                "every ladder from {} to {} goes back through a word that has already been visited",
+                start, goal
+            ),
+            SolveError::InvalidInput(problems) => {
+                write!(f, "found {} problems with the input:", problems.len())?;
+                for problem in problems {
+                    write!(f, "\n  - {}", problem)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Error for SolveError {}
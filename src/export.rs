@@ -0,0 +1,314 @@
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::io::{self, Write};
+
+use crate::error::SolveError;
+use crate::graph::{Subgraph, WordId};
+use crate::ladder::Ladder;
+use crate::moves::{MoveKind, MoveSet};
+use crate::search::{find_all_shortest_paths, Queue};
+use crate::validate::validate_waypoints;
+
+/// Which part of a word graph to export with
+/// [`WordGraph::export`](crate::WordGraph::export)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExportScope<'w> {
+    /// Every word of the graph and every move between them
+    Whole,
+    /// Every word that a ladder from `word` can reach
+    Component(&'w str),
+    /// Every word at most `hops` moves away from `word`
+    Neighborhood { word: &'w str, hops: usize },
+    /// Every word and move on at least one shortest ladder from `start` to
+    /// `goal`, directed from the start towards the goal
+    ShortestLadders { start: &'w str, goal: &'w str },
+}
+
+/// The file formats a [`GraphExport`] can be written in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ExportFormat {
+    /// The Graphviz DOT language, for drawing with `dot` or `neato`
+    #[default]
+    Dot,
+    /// GraphML, the XML format read by tools such as Gephi and yEd
+    GraphMl,
+}
+
+impl ExportFormat {
+    /// Every format, in the order they are listed in help text
+    pub const ALL: [ExportFormat; 2] = [ExportFormat::Dot, ExportFormat::GraphMl];
+
+    /// The name used for the format on the command line
+    pub fn name(self) -> &'static str {
+        match self {
+            ExportFormat::Dot => "dot",
+            ExportFormat::GraphMl => "graphml",
+        }
+    }
+}
+
+impl fmt::Display for ExportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// A part of a word graph, picked out by an [`ExportScope`] and ready to be
+/// written out for drawing. A ladder can be highlighted on top of it with
+/// [`GraphExport::highlight`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphExport<'a> {
+    /// The words, in dictionary order
+    words: Vec<&'a str>,
+    /// Each move once, as positions in `words`. Undirected moves have the
+    /// earlier word first.
+    moves: Vec<(usize, usize, MoveKind)>,
+    directed: bool,
+    /// The kinds of move of the graph the export was taken from, so edges
+    /// are only labelled when there is more than one kind
+    kinds: MoveSet,
+    /// The highlighted words, and the highlighted moves as in `moves`
+    ladder_words: HashSet<usize>,
+    ladder_moves: HashSet<(usize, usize)>,
+}
+
+impl<'a> GraphExport<'a> {
+    /// Every word of the export, in dictionary order
+    pub fn words(&self) -> &[&'a str] {
+        &self.words
+    }
+
+    /// Every move of the export, with the kind of move it is
+    pub fn moves(&self) -> impl Iterator<Item = (&'a str, &'a str, MoveKind)> + '_ {
+        self.moves.iter().map(|&(from, to, kind)| (self.words[from], self.words[to], kind))
+    }
+
+    /// Returns whether the moves only go one way, from the start of a ladder
+    /// towards its goal
+    pub fn is_directed(&self) -> bool {
+        self.directed
+    }
+
+    /// Highlights the words and moves of `ladder`. Only the parts of the
+    /// ladder that are in the export can be highlighted; the rest of it is
+    /// left out rather than added.
+    pub fn highlight(&mut self, ladder: &Ladder) -> &mut Self {
+        let positions: HashMap<&str, usize> = self.words.iter().enumerate().map(|(i, &word)| (word, i)).collect();
+        let on_ladder: Vec<Option<usize>> = ladder.iter().map(|word| positions.get(word).copied()).collect();
+        self.ladder_words.extend(on_ladder.iter().flatten());
+        for step in on_ladder.windows(2) {
+            if let [Some(from), Some(to)] = *step {
+                let step = if self.directed { (from, to) } else { (from.min(to), from.max(to)) };
+                if self.moves.iter().any(|&(v, w, _)| (v, w) == step) {
+                    self.ladder_moves.insert(step);
+                }
+            }
+        }
+        self
+    }
+
+    /// Writes the export to `out` in the given format
+    pub fn write(&self, format: ExportFormat, out: &mut dyn Write) -> io::Result<()> {
+        match format {
+            ExportFormat::Dot => self.write_dot(out),
+            ExportFormat::GraphMl => self.write_graphml(out),
+        }
+    }
+
+    /// Writes the export as a Graphviz graph, with the highlighted ladder
+    /// drawn thick and in red
+    fn write_dot(&self, out: &mut dyn Write) -> io::Result<()> {
+        let (keyword, arrow) = if self.directed { ("digraph", "->") } else { ("graph", "--") };
+        writeln!(out, "{} words {{", keyword)?;
+        for (i, word) in self.words.iter().enumerate() {
+            if self.ladder_words.contains(&i) {
+                writeln!(out, "  \"{}\" [color=red, penwidth=2];", word)?;
+            } else {
+                writeln!(out, "  \"{}\";", word)?;
+            }
+        }
+        for &(from, to, kind) in self.moves.iter() {
+            let mut attributes = Vec::new();
+            if self.kinds.kinds().count() > 1 {
+                attributes.push(format!("label=\"{}\"", kind));
+            }
+            if self.ladder_moves.contains(&(from, to)) {
+                attributes.push("color=red, penwidth=2".to_string());
+            }
+            write!(out, "  \"{}\" {} \"{}\"", self.words[from], arrow, self.words[to])?;
+            if !attributes.is_empty() {
+                write!(out, " [{}]", attributes.join(", "))?;
+            }
+            writeln!(out, ";")?;
+        }
+        writeln!(out, "}}")
+    }
+
+    /// Writes the export as GraphML, with the kind of each move and whether
+    /// each word and move is on the highlighted ladder as data attributes
+    fn write_graphml(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(out, r#"<graphml xmlns="http://graphml.graphdrawing.org/xmlns">"#)?;
+        writeln!(out, r#"  <key id="kind" for="edge" attr.name="kind" attr.type="string"/>"#)?;
+        writeln!(out, r#"  <key id="ladder" for="all" attr.name="ladder" attr.type="boolean">"#)?;
+        writeln!(out, r#"    <default>false</default>"#)?;
+        writeln!(out, r#"  </key>"#)?;
+        let edges = if self.directed { "directed" } else { "undirected" };
+        writeln!(out, r#"  <graph id="words" edgedefault="{}">"#, edges)?;
+        for (i, word) in self.words.iter().enumerate() {
+            if self.ladder_words.contains(&i) {
+                writeln!(out, r#"    <node id="{}"><data key="ladder">true</data></node>"#, word)?;
+            } else {
+                writeln!(out, r#"    <node id="{}"/>"#, word)?;
+            }
+        }
+        for &(from, to, kind) in self.moves.iter() {
+            write!(out, r#"    <edge source="{}" target="{}">"#, self.words[from], self.words[to])?;
+            write!(out, r#"<data key="kind">{}</data>"#, kind)?;
+            if self.ladder_moves.contains(&(from, to)) {
+                write!(out, r#"<data key="ladder">true</data>"#)?;
+            }
+            writeln!(out, "</edge>")?;
+        }
+        writeln!(out, "  </graph>")?;
+        writeln!(out, "</graphml>")
+    }
+}
+
+/// The words at most `hops` moves away from `root`, or every word a ladder
+/// from `root` can reach if there is no limit, in dictionary order
+fn words_around(graph: Subgraph, root: WordId, hops: Option<usize>) -> Vec<WordId> {
+    let mut distance = HashMap::from([(root, 0)]);
+    let mut q = Queue::new();
+    q.enqueue(root);
+    while let Some(v) = q.dequeue() {
+        let next = distance[&v] + 1;
+        if hops.is_some_and(|hops| next > hops) {
+            continue
+        }
+        for w in graph.neighbors(v) {
+            if let Entry::Vacant(entry) = distance.entry(w) {
+                entry.insert(next);
+                q.enqueue(w);
+            }
+        }
+    }
+    let mut words: Vec<WordId> = distance.into_keys().collect();
+    words.sort_unstable();
+
+    words
+}
+
+/// Picks out the words and moves of `scope`. Every scope but the shortest
+/// ladders takes every move between the words it picks, each one once.
+pub(crate) fn export_scope<'a>(graph: Subgraph<'_, 'a>, scope: ExportScope) -> Result<GraphExport<'a>, SolveError> {
+    let mut moves = Vec::new();
+    let ids = match scope {
+        ExportScope::ShortestLadders { start, goal } => {
+            let dag = find_all_shortest_paths(graph, start, goal)?;
+            let mut ids: Vec<WordId> = dag.children.keys().copied().chain([dag.goal]).collect();
+            ids.sort_unstable();
+            for &v in ids.iter() {
+                moves.extend(dag.children(v).iter().map(|&w| (v, w)));
+            }
+            ids
+        }
+        ExportScope::Whole => (0..graph.len() as WordId).filter(|&id| !graph.is_removed(id)).collect(),
+        ExportScope::Component(word) => words_around(graph, validate_waypoints(graph, &[word])?[0], None),
+        ExportScope::Neighborhood { word, hops } => {
+            words_around(graph, validate_waypoints(graph, &[word])?[0], Some(hops))
+        }
+    };
+
+    let positions: HashMap<WordId, usize> = ids.iter().enumerate().map(|(i, &id)| (id, i)).collect();
+    let directed = matches!(scope, ExportScope::ShortestLadders { .. });
+    if !directed {
+        for &v in ids.iter() {
+            moves.extend(graph.neighbors(v).filter(|&w| v < w && positions.contains_key(&w)).map(|w| (v, w)));
+        }
+    }
+    let moves = moves
+        .into_iter()
+        .filter_map(|(v, w)| Some((positions[&v], positions[&w], graph.move_kind(v, w)?)))
+        .collect();
+
+    Ok(GraphExport {
+        words: ids.into_iter().map(|id| graph.word(id)).collect(),
+        moves,
+        directed,
+        kinds: graph.moves(),
+        ladder_words: HashSet::new(),
+        ladder_moves: HashSet::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WordGraph;
+
+    const WORDS: [&str; 9] = ["card", "cold", "cord", "dork", "ward", "warm", "word", "work", "zzzz"];
+
+    /// The moves of `export` as pairs of words, without their kinds
+    fn moves<'a>(export: &GraphExport<'a>) -> Vec<(&'a str, &'a str)> {
+        export.moves().map(|(from, to, _)| (from, to)).collect()
+    }
+
+    #[test]
+    fn scopes_pick_out_their_words_and_moves() {
+        let graph = WordGraph::from_dict(&WORDS);
+        let alone = graph.export(ExportScope::Neighborhood { word: "word", hops: 0 }).unwrap();
+        assert_eq!(alone.words(), ["word"]);
+        assert!(moves(&alone).is_empty());
+
+        let around = graph.export(ExportScope::Neighborhood { word: "word", hops: 1 }).unwrap();
+        assert_eq!(around.words(), ["cord", "ward", "word", "work"]);
+        assert_eq!(moves(&around), [("cord", "word"), ("ward", "word"), ("word", "work")]);
+
+        let component = graph.export(ExportScope::Component("word")).unwrap();
+        assert_eq!(component.words(), &WORDS[..8]);
+        assert_eq!(
+            moves(&component),
+            [
+                ("card", "cord"),
+                ("card", "ward"),
+                ("cold", "cord"),
+                ("cord", "word"),
+                ("dork", "work"),
+                ("ward", "warm"),
+                ("ward", "word"),
+                ("word", "work"),
+            ]
+        );
+        assert!(!component.is_directed());
+
+        let ladders = graph.export(ExportScope::ShortestLadders { start: "word", goal: "dork" }).unwrap();
+        assert_eq!(ladders.words(), ["dork", "word", "work"]);
+        assert_eq!(moves(&ladders), [("word", "work"), ("work", "dork")]);
+        assert!(ladders.is_directed());
+    }
+
+    #[test]
+    fn highlighted_moves_follow_the_direction_of_the_export() {
+        let graph = WordGraph::from_dict(&WORDS);
+        let scope = ExportScope::ShortestLadders { start: "word", goal: "dork" };
+        let mut forward = graph.export(scope).unwrap();
+        forward.highlight(&graph.shortest_path("word", "dork").unwrap());
+        let mut dot = Vec::new();
+        forward.write(ExportFormat::Dot, &mut dot).unwrap();
+        let dot = String::from_utf8(dot).unwrap();
+        assert!(dot.contains("\"word\" -> \"work\" [color=red, penwidth=2];"));
+        assert!(dot.contains("\"work\" -> \"dork\" [color=red, penwidth=2];"));
+
+        // the same ladder walked backwards runs against every move
+        let mut backward = graph.export(scope).unwrap();
+        backward.highlight(&graph.shortest_path("dork", "word").unwrap());
+        assert_eq!(backward.ladder_words.len(), 3);
+        assert!(backward.ladder_moves.is_empty());
+
+        let mut undirected = graph.export(ExportScope::Component("word")).unwrap();
+        undirected.highlight(&graph.shortest_path("dork", "word").unwrap());
+        assert_eq!(undirected.ladder_moves.len(), 2);
+    }
+}
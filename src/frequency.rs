@@ -0,0 +1,128 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::dag::Dag;
+use crate::graph::{Subgraph, WordId};
+use crate::validate::is_allowed_char;
+
+/// How often each word is used, for telling common words from obscure ones.
+/// Each word's rarity is the negative logarithm of how often it is used, so
+/// the ladder with the lowest total rarity is the one whose words are most
+/// likely to be seen together, and a single very obscure word costs more than
+/// a couple of slightly uncommon ones.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct WordFrequencies {
+    counts: HashMap<String, u64>,
+    total: u64,
+}
+
+impl WordFrequencies {
+    /// Builds the frequencies from pairs of a word and the number of times it
+    /// was seen. Words are lowercased like those of a [`Dictionary`], and the
+    /// counts of words that appear more than once are added up.
+    ///
+    /// [`Dictionary`]: crate::Dictionary
+    pub fn from_counts<I, S>(counts: I) -> Self
+    where
+        I: IntoIterator<Item = (S, u64)>,
+        S: AsRef<str>,
+    {
+        let mut frequencies = WordFrequencies::default();
+        for (word, count) in counts {
+            let word = word.as_ref().trim().to_lowercase();
+            if word.is_empty() || !word.chars().all(is_allowed_char) {
+                continue
+            }
+            *frequencies.counts.entry(word).or_default() += count;
+            frequencies.total += count;
+        }
+
+        frequencies
+    }
+
+    /// Parses a frequency list with one word per line, followed by its count
+    /// and separated from it by whitespace or a comma, as in `word 1234`.
+    /// Lines that don't have a count, such as a header, are skipped.
+    pub fn parse(list: &str) -> Self {
+        WordFrequencies::from_counts(list.lines().filter_map(|line| {
+            let (word, count) = line.trim().split_once(|c: char| c == ',' || c.is_whitespace())?;
+            Some((word, count.trim().parse().ok()?))
+        }))
+    }
+
+    /// Reads a frequency list from a file
+    pub fn from_file<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Ok(WordFrequencies::parse(&fs::read_to_string(path)?))
+    }
+
+    /// The number of times `word` was seen, which is zero for a word that
+    /// isn't in the list
+    pub fn count(&self, word: &str) -> u64 {
+        self.counts.get(word).copied().unwrap_or(0)
+    }
+
+    /// How obscure `word` is: the negative logarithm of its share of all the
+    /// words seen. A word that isn't in the list is treated as if it had been
+    /// seen less than once, so it is rarer than any word that is.
+    pub fn rarity(&self, word: &str) -> f64 {
+        let count = match self.count(word) {
+            0 => 0.5,
+            count => count as f64,
+        };
+        ((self.total as f64 + 1.0) / count).ln()
+    }
+
+    /// The number of distinct words in the list
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    /// Returns whether the list has no words in it at all
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+}
+
+/// Picks the shortest ladder made of the most common words out of the graph
+/// of every shortest ladder. The least total rarity from each word to the
+/// goal only depends on the words after it, so it is worked out once per word,
+/// from the goal backwards, and the ladder follows the best child from the
+/// start. Ties go to the child that comes first in dictionary order.
+pub(crate) fn most_common_ladder(graph: Subgraph, dag: &Dag, frequencies: &WordFrequencies) -> Vec<WordId> {
+    fn best_from(
+        v: WordId,
+        graph: Subgraph,
+        dag: &Dag,
+        frequencies: &WordFrequencies,
+        best: &mut HashMap<WordId, (f64, Option<WordId>)>,
+    ) -> f64 {
+        if let Some(&(rarity, _)) = best.get(&v) {
+            return rarity
+        }
+        let mut choice: (f64, Option<WordId>) = (f64::INFINITY, None);
+        for &w in dag.children(v) {
+            let rarity = best_from(w, graph, dag, frequencies, best);
+            if rarity < choice.0 {
+                choice = (rarity, Some(w));
+            }
+        }
+        if v == dag.goal {
+            choice.0 = 0.0;
+        }
+        choice.0 += frequencies.rarity(graph.word(v));
+        best.insert(v, choice);
+
+        choice.0
+    }
+
+    let mut best = HashMap::new();
+    best_from(dag.start, graph, dag, frequencies, &mut best);
+    let mut path = vec![dag.start];
+    while let Some(&(_, Some(next))) = best.get(&path[path.len() - 1]) {
+        path.push(next);
+    }
+
+    path
+}
@@ -0,0 +1,852 @@
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+
+use crate::dictionary::Dictionary;
+use crate::error::SolveError;
+use crate::ladder::Ladder;
+use crate::moves::{MoveKind, MoveSet};
+use crate::bignum::BigUint;
+use crate::bitset::BitSet;
+use crate::cache::GraphCache;
+use crate::constraints::Constraints;
+use crate::cost::{CheapestLadder, CostModel};
+use crate::dag::ShortestLadders;
+use crate::dijkstra::find_cheapest_path;
+use crate::export::{export_scope, ExportScope, GraphExport};
+use crate::frequency::{most_common_ladder, WordFrequencies};
+use crate::precomputed::precomputed_graph;
+use crate::search::{
+    count_shortest_paths, find_all_shortest_paths, find_k_shortest_paths, find_shortest_path, Strategy,
+};
+use crate::tour::find_best_tour;
+use crate::validate::normalize;
+use crate::waypoints::find_path_via;
+
+/// Words are referred to by their position in the graph's interning table,
+/// which is much cheaper to hash, store and compare than the word itself
+pub(crate) type WordId = u32;
+
+/// The word ladder data in compressed sparse row form. Every word is interned
+/// as a `WordId`, and the neighbours of word `id` are the slice
+/// `targets[offsets[id]..offsets[id + 1]]`, so walking the edges of a word is a
+/// pair of array lookups rather than a hash lookup and a pointer chase, and the
+/// whole adjacency lives in two flat arrays. Each word is also labelled with
+/// the connected component it belongs to, so that two words with no ladder
+/// between them can be told apart without searching. Every edge is labelled
+/// with the kind of move it stands for, in `kinds`, which runs parallel to
+/// `targets`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Graph<'g> {
+    words: Vec<&'g str>,
+    ids: HashMap<&'g str, WordId>,
+    offsets: Vec<u32>,
+    targets: Vec<WordId>,
+    kinds: Vec<MoveKind>,
+    components: Vec<u32>,
+    moves: MoveSet,
+}
+
+impl<'g> Graph<'g> {
+    /// Packs per-word neighbour lists into a graph. `neighbors[i]` lists the
+    /// ids of the neighbours of `words[i]`, each with the kind of move that
+    /// connects them, out of the given `moves`.
+    fn from_neighbors(words: Vec<&'g str>, neighbors: Vec<Vec<(WordId, MoveKind)>>, moves: MoveSet) -> Self {
+        let edges = neighbors.iter().map(Vec::len).sum();
+        let mut offsets = Vec::with_capacity(words.len() + 1);
+        let mut targets = Vec::with_capacity(edges);
+        let mut kinds = Vec::with_capacity(edges);
+        offsets.push(0);
+        for connections in neighbors {
+            for (target, kind) in connections {
+                targets.push(target);
+                kinds.push(kind);
+            }
+            offsets.push(targets.len() as u32);
+        }
+
+        Graph::from_parts(words, offsets, targets, kinds, moves)
+    }
+
+    /// Assembles a graph whose edges are already packed, as when it is read
+    /// back from a [`GraphCache`](crate::GraphCache). Only the lookup table
+    /// and the components are worked out again.
+    pub(crate) fn from_parts(
+        words: Vec<&'g str>,
+        offsets: Vec<u32>,
+        targets: Vec<WordId>,
+        kinds: Vec<MoveKind>,
+        moves: MoveSet,
+    ) -> Self {
+        let ids = words.iter().enumerate().map(|(id, &word)| (word, id as WordId)).collect();
+        let mut graph = Graph { words, ids, offsets, targets, kinds, components: Vec::new(), moves };
+        graph.components = graph.label_components();
+        graph
+    }
+
+    /// The packed edges: the offsets of each word's neighbours, the
+    /// neighbours themselves and the kind of move to each of them
+    pub(crate) fn edges(&self) -> (&[u32], &[WordId], &[MoveKind]) {
+        (&self.offsets, &self.targets, &self.kinds)
+    }
+
+    /// Labels every word with the connected component it is in, numbering the
+    /// components in the order their first word appears in the graph
+    fn label_components(&self) -> Vec<u32> {
+        const UNLABELLED: u32 = u32::MAX;
+        let mut components = vec![UNLABELLED; self.len()];
+        let mut next_label = 0;
+        let mut stack = Vec::new();
+        for root in 0..self.len() as WordId {
+            if components[root as usize] != UNLABELLED {
+                continue
+            }
+            components[root as usize] = next_label;
+            stack.push(root);
+            while let Some(v) = stack.pop() {
+                for &w in self.neighbors(v) {
+                    if components[w as usize] == UNLABELLED {
+                        components[w as usize] = next_label;
+                        stack.push(w);
+                    }
+                }
+            }
+            next_label += 1;
+        }
+
+        components
+    }
+
+    /// Looks up the id of `word`, if it is in the graph
+    pub(crate) fn id(&self, word: &str) -> Option<WordId> {
+        self.ids.get(word).copied()
+    }
+
+    /// The word interned as `id`
+    pub(crate) fn word(&self, id: WordId) -> &'g str {
+        self.words[id as usize]
+    }
+
+    /// The ids of the words one move away from word `id`
+    pub(crate) fn neighbors(&self, id: WordId) -> &[WordId] {
+        let id = id as usize;
+        &self.targets[self.offsets[id] as usize..self.offsets[id + 1] as usize]
+    }
+
+    /// The kind of move that takes word `from` to word `to`, if they are
+    /// neighbours. Neighbours are kept in order of id, so this is a binary
+    /// search.
+    pub(crate) fn move_kind(&self, from: WordId, to: WordId) -> Option<MoveKind> {
+        let start = self.offsets[from as usize] as usize;
+        let i = self.neighbors(from).binary_search(&to).ok()?;
+        Some(self.kinds[start + i])
+    }
+
+    /// Returns whether there is any ladder at all between two words
+    pub(crate) fn connected(&self, a: WordId, b: WordId) -> bool {
+        self.components[a as usize] == self.components[b as usize]
+    }
+
+    /// The number of words in the graph
+    pub(crate) fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    /// The kinds of move the graph's edges stand for
+    pub(crate) fn moves(&self) -> MoveSet {
+        self.moves
+    }
+}
+
+/// The graph with some of its words taken out for the length of a search,
+/// for example because the caller has excluded them from the ladder. Searches
+/// run on a `Subgraph` rather than on the `Graph` directly, and see the removed
+/// words as if they had never been in the dictionary, so excluding words never
+/// means building the graph again.
+#[derive(Clone, Copy)]
+pub(crate) struct Subgraph<'s, 'g> {
+    graph: &'s Graph<'g>,
+    removed: Option<&'s BitSet>,
+}
+
+impl<'s, 'g> Subgraph<'s, 'g> {
+    /// The graph without the words in `removed`. When nothing is removed
+    /// this is just the whole graph, so searches don't check every neighbour
+    /// against an empty set or treat the graph as restricted.
+    pub(crate) fn without(graph: &'s Graph<'g>, removed: &'s BitSet) -> Self {
+        Subgraph { graph, removed: (!removed.is_empty()).then_some(removed) }
+    }
+
+    /// The graph underneath, with nothing removed
+    pub(crate) fn graph(&self) -> &'s Graph<'g> {
+        self.graph
+    }
+
+    /// A copy of the set of removed words, for building a smaller subgraph
+    /// of the same graph
+    pub(crate) fn removed_words(&self) -> BitSet {
+        self.removed.cloned().unwrap_or_else(|| BitSet::new(self.len()))
+    }
+
+    /// Returns whether any words have been removed at all
+    pub(crate) fn is_restricted(&self) -> bool {
+        self.removed.is_some()
+    }
+
+    /// Returns whether word `id` has been removed
+    pub(crate) fn is_removed(&self, id: WordId) -> bool {
+        self.removed.is_some_and(|removed| removed.contains(id))
+    }
+
+    /// Looks up the id of `word`, if it is in the graph, whether or not it
+    /// has been removed
+    pub(crate) fn id(&self, word: &str) -> Option<WordId> {
+        self.graph.id(word)
+    }
+
+    /// The word interned as `id`
+    pub(crate) fn word(&self, id: WordId) -> &'g str {
+        self.graph.word(id)
+    }
+
+    /// The ids of the words one move away from word `id` that haven't been
+    /// removed
+    pub(crate) fn neighbors(&self, id: WordId) -> impl Iterator<Item = WordId> + 's {
+        let removed = self.removed;
+        self.graph
+            .neighbors(id)
+            .iter()
+            .copied()
+            .filter(move |&w| removed.is_none_or(|removed| !removed.contains(w)))
+    }
+
+    /// The kind of move that takes word `from` to word `to`, if they are
+    /// neighbours
+    pub(crate) fn move_kind(&self, from: WordId, to: WordId) -> Option<MoveKind> {
+        self.graph.move_kind(from, to)
+    }
+
+    /// Returns whether there could be a ladder between two words. Words in
+    /// different components of the whole graph certainly have none, but
+    /// removing words can cut a component in two, so this is only a quick
+    /// way to rule ladders out.
+    pub(crate) fn connected(&self, a: WordId, b: WordId) -> bool {
+        self.graph.connected(a, b)
+    }
+
+    /// The number of words in the whole graph, which is also one more than
+    /// the largest word id
+    pub(crate) fn len(&self) -> usize {
+        self.graph.len()
+    }
+
+    /// The kinds of move the graph's edges stand for
+    pub(crate) fn moves(&self) -> MoveSet {
+        self.graph.moves()
+    }
+}
+
+/// Determines whether two strings of the same length
+/// differ by only one character. Strings of different lengths
+/// are never one character apart by substitution, so we check
+/// that first rather than letting `zip` quietly ignore the
+/// extra characters of the longer one.
+pub(crate) fn is_one_char_diff(s1: &str, s2: &str) -> bool {
+    if s1.len() != s2.len() {
+        return false
+    }
+
+    let iter = s1.chars().zip(s2.chars());
+    let mut counter = 0;
+
+    for (c1, c2) in iter {
+        if c1 != c2 {
+            counter += 1;
+        }
+    }
+
+    counter == 1
+}
+
+/// Drops repeated words from `dict`, keeping the first occurrence of each, so
+/// that every word is interned exactly once
+pub(crate) fn unique_words<'a>(dict: &[&'a str]) -> Vec<&'a str> {
+    let mut seen = HashSet::with_capacity(dict.len());
+    dict.iter().copied().filter(|&word| seen.insert(word)).collect()
+}
+
+/// Every wildcard pattern a word matches when any one of its letters is
+/// allowed to change, e.g. `word` gives `_ord`, `w_rd`, `wo_d` and `wor_`.
+/// Two words differ by a single letter exactly when they share one of these.
+fn wildcard_patterns(word: &str) -> impl Iterator<Item = String> + '_ {
+    word.char_indices().map(move |(i, c)| {
+        let mut pattern = String::with_capacity(word.len());
+        pattern.push_str(&word[..i]);
+        pattern.push('_');
+        pattern.push_str(&word[i + c.len_utf8()..]);
+        pattern
+    })
+}
+
+/// Connects every word filed in a bucket to all the others in the same bucket
+/// by a move of the given kind
+fn connect_buckets<'b>(
+    buckets: impl Iterator<Item = &'b Vec<WordId>>,
+    kind: MoveKind,
+    neighbors: &mut [Vec<(WordId, MoveKind)>],
+) {
+    for bucket in buckets {
+        for &id in bucket {
+            neighbors[id as usize].extend(bucket.iter().filter(|&&other| other != id).map(|&other| (other, kind)));
+        }
+    }
+}
+
+/// Every word made by swapping two neighbouring letters of `word` that are
+/// different from each other, e.g. `form` gives `ofrm`, `from` and `fomr`
+fn adjacent_swaps(word: &str) -> impl Iterator<Item = String> + '_ {
+    let letters = word.as_bytes();
+    (1..letters.len()).filter(move |&i| letters[i - 1] != letters[i]).map(move |i| {
+        let mut swapped = letters.to_vec();
+        swapped.swap(i - 1, i);
+        // the dictionary only holds ASCII letters, so this is still valid UTF-8
+        String::from_utf8(swapped).unwrap_or_default()
+    })
+}
+
+/// Every word made by taking a single letter out of `word`, e.g. `cart` gives
+/// `art`, `crt`, `cat` and `car`. A word with a doubled letter gives the same
+/// shorter word more than once.
+fn deletions(word: &str) -> impl Iterator<Item = String> + '_ {
+    word.char_indices().map(move |(i, c)| {
+        let mut shorter = String::with_capacity(word.len());
+        shorter.push_str(&word[..i]);
+        shorter.push_str(&word[i + c.len_utf8()..]);
+        shorter
+    })
+}
+
+/// Takes a dictionary of words and then builds the graph of words
+/// that are connected one they differ by a single letter only.
+/// Rather than comparing every pair of words, each word is filed under
+/// each of its wildcard patterns, and all the words filed under the same
+/// pattern are connected to one another. That makes construction roughly
+/// O(n·L) for n words of length L instead of O(n²). Every word gets an id,
+/// even one with no neighbours, so that an isolated word is still recognised
+/// as part of the dictionary. Neighbours are listed in dictionary order, which
+/// is the same order the pairwise construction produced them in, so searches
+/// give the same ladders either way. Only the kinds of move in `moves` are
+/// connected; substitutions are just the default. The other kinds are found
+/// in the same spirit, by looking words up rather than comparing pairs: every
+/// deletion or neighbouring swap of a word is looked up directly, and anagrams
+/// are filed together under their letters in sorted order. The graph of the
+/// built-in dictionary with the default moves isn't built at all, since the
+/// build script has already worked it out.
+pub(crate) fn build_graph_from_dict<'a>(dict: &[&'a str], moves: MoveSet) -> Graph<'a> {
+    if let Some(graph) = precomputed_graph(dict, moves) {
+        return graph
+    }
+    let words = unique_words(dict);
+    let ids: HashMap<&str, WordId> = words.iter().enumerate().map(|(id, &word)| (word, id as WordId)).collect();
+    let mut neighbors: Vec<Vec<(WordId, MoveKind)>> = vec![Vec::new(); words.len()];
+    if moves.contains(MoveKind::Substitute) {
+        let mut buckets: HashMap<String, Vec<WordId>> = HashMap::new();
+        for (id, word) in words.iter().enumerate() {
+            for pattern in wildcard_patterns(word) {
+                buckets.entry(pattern).or_default().push(id as WordId);
+            }
+        }
+        connect_buckets(buckets.values(), MoveKind::Substitute, &mut neighbors);
+    }
+    if moves.contains(MoveKind::InsertDelete) {
+        // inserting a letter into a word is deleting it from the longer word,
+        // so looking up every deletion of every word finds each pair once
+        for (id, word) in words.iter().enumerate() {
+            for shorter in deletions(word) {
+                if let Some(&other) = ids.get(shorter.as_str()) {
+                    neighbors[id].push((other, MoveKind::InsertDelete));
+                    neighbors[other as usize].push((id as WordId, MoveKind::InsertDelete));
+                }
+            }
+        }
+    }
+    if moves.contains(MoveKind::Swap) {
+        // a swap undoes itself, so each word only records the pairs it finds
+        // and the other word records the same pair from its own side
+        for (id, word) in words.iter().enumerate() {
+            for swapped in adjacent_swaps(word) {
+                if let Some(&other) = ids.get(swapped.as_str()) {
+                    neighbors[id].push((other, MoveKind::Swap));
+                }
+            }
+        }
+    }
+    if moves.contains(MoveKind::Anagram) {
+        let mut buckets: HashMap<Vec<u8>, Vec<WordId>> = HashMap::new();
+        for (id, word) in words.iter().enumerate() {
+            let mut letters = word.as_bytes().to_vec();
+            letters.sort_unstable();
+            buckets.entry(letters).or_default().push(id as WordId);
+        }
+        connect_buckets(buckets.values(), MoveKind::Anagram, &mut neighbors);
+    }
+    // sorting puts each neighbour's most specific kind of move first, which
+    // is the one kept
+    for connections in neighbors.iter_mut() {
+        connections.sort_unstable();
+        connections.dedup_by_key(|&mut (other, _)| other);
+    }
+
+    Graph::from_neighbors(words, neighbors, moves)
+}
+
+/// The original way of building the graph: take a dictionary of words and compare
+/// every pair of them, connecting the ones that differ by a single letter only.
+/// This is O(n²) in the size of the dictionary, so it is only kept around as a
+/// reference to check and benchmark `build_graph_from_dict` against.
+/// Because connections are symmetric, we insert both directions at once
+/// and then we only have to examine half of all possible pairs of words.
+pub(crate) fn build_graph_pairwise<'a>(dict: &[&'a str]) -> Graph<'a> {
+    let words = unique_words(dict);
+    let mut neighbors: Vec<Vec<(WordId, MoveKind)>> = vec![Vec::new(); words.len()];
+    for i in 0..words.len() {
+        // because we will insert connections symmetrically, we only need
+        // to check pairs from `i + 1` forward
+        for j in i + 1..words.len() {
+            if is_one_char_diff(words[i], words[j]) {
+                neighbors[i].push((j as WordId, MoveKind::Substitute));
+                neighbors[j].push((i as WordId, MoveKind::Substitute));
+            }
+        }
+    }
+
+    Graph::from_neighbors(words, neighbors, MoveSet::default())
+}
+
+/// The graph of every word in a dictionary, where two words are neighbours
+/// when they differ by a single letter, or by whichever other kinds of move
+/// the graph was built with. The graph borrows its words from the
+/// dictionary it was built from, so it can be built once and then queried
+/// for as many ladders as needed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WordGraph<'a> {
+    graph: Graph<'a>,
+}
+
+impl<'a> WordGraph<'a> {
+    /// Builds the graph from a list of words
+    pub fn from_dict(dict: &[&'a str]) -> Self {
+        WordGraph::from_dict_with_moves(dict, MoveSet::default())
+    }
+
+    /// Builds the graph from a list of words, connecting the words that are
+    /// one of the given kinds of move apart
+    pub fn from_dict_with_moves(dict: &[&'a str], moves: MoveSet) -> Self {
+        WordGraph { graph: build_graph_from_dict(dict, moves) }
+    }
+
+    /// Loads the graph of a list of words with the given kinds of move from
+    /// `cache`, building it and saving it there if it hasn't been saved yet.
+    /// This gives exactly the same graph as [`WordGraph::from_dict_with_moves`].
+    pub fn from_dict_cached(dict: &[&'a str], moves: MoveSet, cache: &GraphCache) -> Self {
+        WordGraph { graph: cache.load_or_build(dict, moves) }
+    }
+
+    /// Builds the graph by comparing every pair of words in `dict`. This gives
+    /// exactly the same graph as [`WordGraph::from_dict`] but takes time
+    /// quadratic in the number of words, so it is only useful as a baseline
+    /// for comparison.
+    pub fn from_dict_pairwise(dict: &[&'a str]) -> Self {
+        WordGraph { graph: build_graph_pairwise(dict) }
+    }
+
+    /// Builds the graph from a loaded [`Dictionary`], covering words of every
+    /// length in it
+    pub fn from_dictionary(dictionary: &'a Dictionary) -> Self {
+        WordGraph::from_dict(&dictionary.words())
+    }
+
+    /// Builds the graph from a loaded [`Dictionary`] with the given kinds of
+    /// move. When the moves can change the length of a word, words of every
+    /// length can end up on the same ladder, so this is the way to build a
+    /// graph for them.
+    pub fn from_dictionary_with_moves(dictionary: &'a Dictionary, moves: MoveSet) -> Self {
+        WordGraph::from_dict_with_moves(&dictionary.words(), moves)
+    }
+
+    /// Loads the graph of a loaded [`Dictionary`] with the given kinds of move
+    /// from `cache`, like [`WordGraph::from_dict_cached`]
+    pub fn from_dictionary_cached(dictionary: &'a Dictionary, moves: MoveSet, cache: &GraphCache) -> Self {
+        WordGraph::from_dict_cached(&dictionary.words(), moves, cache)
+    }
+
+    /// Builds the graph from only the words of a [`Dictionary`] with one of
+    /// the given lengths. Words of different lengths can never be on the same
+    /// ladder, so only the lengths that a query actually needs have to be
+    /// built at all.
+    pub fn for_lengths(dictionary: &'a Dictionary, lengths: &[usize]) -> Self {
+        WordGraph::for_lengths_with_moves(dictionary, lengths, MoveSet::default())
+    }
+
+    /// Builds the graph from only the words of a [`Dictionary`] with one of
+    /// the given lengths, with the given kinds of move. This only makes sense
+    /// for moves that never change the length of a word.
+    pub fn for_lengths_with_moves(dictionary: &'a Dictionary, lengths: &[usize], moves: MoveSet) -> Self {
+        let words: Vec<&str> = lengths.iter().flat_map(|&len| dictionary.bucket(len)).collect();
+
+        WordGraph::from_dict_with_moves(&words, moves)
+    }
+
+    /// Loads the graph of only the words of a [`Dictionary`] with one of the
+    /// given lengths from `cache`, like [`WordGraph::from_dict_cached`]. Each
+    /// set of lengths is cached as a graph of its own.
+    pub fn for_lengths_cached(
+        dictionary: &'a Dictionary,
+        lengths: &[usize],
+        moves: MoveSet,
+        cache: &GraphCache,
+    ) -> Self {
+        let words: Vec<&str> = lengths.iter().flat_map(|&len| dictionary.bucket(len)).collect();
+
+        WordGraph::from_dict_cached(&words, moves, cache)
+    }
+
+    /// Returns whether `word` is in the dictionary the graph was built from
+    pub fn contains(&self, word: &str) -> bool {
+        self.graph.id(word).is_some()
+    }
+
+    /// Returns the words one move away from `word`
+    pub fn neighbors(&self, word: &str) -> impl Iterator<Item = &'a str> + '_ {
+        let neighbors = match self.graph.id(word) {
+            Some(id) => self.graph.neighbors(id),
+            None => &[],
+        };
+        neighbors.iter().map(|&id| self.graph.word(id))
+    }
+
+    /// The number of words in the graph
+    pub fn len(&self) -> usize {
+        self.graph.len()
+    }
+
+    /// The kinds of move that connect the words of the graph
+    pub fn moves(&self) -> MoveSet {
+        self.graph.moves()
+    }
+
+    /// The kind of move that takes `from` to `to`, if they are one move
+    /// apart. This is how the moves along a [`Ladder`] can be told apart.
+    pub fn move_kind(&self, from: &str, to: &str) -> Option<MoveKind> {
+        self.graph.move_kind(self.graph.id(from)?, self.graph.id(to)?)
+    }
+
+    /// Returns whether the graph has no words in it at all
+    pub fn is_empty(&self) -> bool {
+        self.graph.len() == 0
+    }
+
+    /// Applies `constraints` to the graph for the searches made through the
+    /// returned view. The graph itself is left as it is, so it can be shared
+    /// by queries with different constraints.
+    pub fn constrained(&self, constraints: &Constraints) -> ConstrainedGraph<'_, 'a> {
+        ConstrainedGraph {
+            graph: &self.graph,
+            removed: constraints.removed_words(&self.graph),
+            endpoints_only: constraints.endpoint_only_words(&self.graph),
+        }
+    }
+
+    /// The graph searched with no constraints at all, which every search of
+    /// the graph itself goes through
+    fn unconstrained(&self) -> ConstrainedGraph<'_, 'a> {
+        self.constrained(&Constraints::new())
+    }
+
+    /// Finds a shortest ladder from `start` to `goal` with the default
+    /// [`Strategy`], or reports why there is none
+    pub fn shortest_path(&self, start: &str, goal: &str) -> Result<Ladder<'a>, SolveError> {
+        self.shortest_path_with(start, goal, Strategy::default())
+    }
+
+    /// Finds a shortest ladder from `start` to `goal` using the given search
+    /// strategy, or reports why there is none
+    pub fn shortest_path_with(&self, start: &str, goal: &str, strategy: Strategy) -> Result<Ladder<'a>, SolveError> {
+        self.unconstrained().shortest_path_with(start, goal, strategy)
+    }
+
+    /// Finds a ladder from `start` to `goal` that visits each of the `via`
+    /// words in order and never visits any word twice. Each segment between
+    /// two consecutive waypoints is a shortest ladder that avoids the words of
+    /// the segments before it, found with the given search strategy.
+    pub fn shortest_path_via(
+        &self,
+        start: &str,
+        via: &[&str],
+        goal: &str,
+        strategy: Strategy,
+    ) -> Result<Ladder<'a>, SolveError> {
+        self.unconstrained().shortest_path_via(start, via, goal, strategy)
+    }
+
+    /// Finds a ladder from `start` to `goal` that visits every one of the
+    /// `visit` words, in whichever order gives the fewest steps in total. The
+    /// best order is found exactly for up to a dozen words and approximately
+    /// for more. The ladder is then built as with
+    /// [`WordGraph::shortest_path_via`], and can come out a little longer
+    /// than the best order promises if the shortest ladders between two pairs
+    /// of words would have to share a word.
+    pub fn shortest_path_visiting(
+        &self,
+        start: &str,
+        visit: &[&str],
+        goal: &str,
+        strategy: Strategy,
+    ) -> Result<Ladder<'a>, SolveError> {
+        self.unconstrained().shortest_path_visiting(start, visit, goal, strategy)
+    }
+
+    /// Finds every shortest ladder from `start` to `goal`. The ladders are
+    /// only built as they are iterated over, so even pairs with a huge number
+    /// of shortest ladders can be capped or counted cheaply.
+    pub fn all_shortest_paths(&self, start: &str, goal: &str) -> Result<ShortestLadders<'_, 'a>, SolveError> {
+        self.unconstrained().all_shortest_paths(start, goal)
+    }
+
+    /// Finds the shortest ladder from `start` to `goal` whose words are the
+    /// most common according to `frequencies`, choosing among every shortest
+    /// ladder rather than taking whichever one the search reaches first
+    pub fn most_common_path(
+        &self,
+        start: &str,
+        goal: &str,
+        frequencies: &WordFrequencies,
+    ) -> Result<Ladder<'a>, SolveError> {
+        self.unconstrained().most_common_path(start, goal, frequencies)
+    }
+
+    /// Counts the shortest ladders from `start` to `goal` without building
+    /// any of them, which stays fast even when there are far too many to list
+    pub fn count_shortest_paths(&self, start: &str, goal: &str) -> Result<BigUint, SolveError> {
+        self.unconstrained().count_shortest_paths(start, goal)
+    }
+
+    /// Finds the ladder from `start` to `goal` whose moves cost the least in
+    /// total under `model`, which need not be a shortest one, along with the
+    /// cost of each of its moves
+    pub fn cheapest_path(&self, start: &str, goal: &str, model: &dyn CostModel) -> Result<CheapestLadder<'a>, SolveError> {
+        self.unconstrained().cheapest_path(start, goal, model)
+    }
+
+    /// Finds up to `k` ladders from `start` to `goal` that never visit the
+    /// same word twice, shortest first. After the shortest ladders come the
+    /// next-best alternatives, so this is useful for seeing how much longer
+    /// the ways around are. Fewer than `k` are returned only when there are
+    /// no more loopless ladders at all.
+    pub fn k_shortest_paths(&self, start: &str, goal: &str, k: usize) -> Result<Vec<Ladder<'a>>, SolveError> {
+        self.unconstrained().k_shortest_paths(start, goal, k)
+    }
+
+    /// Picks out the part of the graph given by `scope`, to be written out
+    /// for drawing as DOT or GraphML
+    pub fn export(&self, scope: ExportScope) -> Result<GraphExport<'a>, SolveError> {
+        self.unconstrained().export(scope)
+    }
+}
+
+/// Builds the graph of every shortest ladder and picks the one made of the
+/// most common words from it
+fn most_common_path<'a>(
+    graph: Subgraph<'_, 'a>,
+    start: &str,
+    goal: &str,
+    frequencies: &WordFrequencies,
+) -> Result<Ladder<'a>, SolveError> {
+    let dag = find_all_shortest_paths(graph, start, goal)?;
+    let path = most_common_ladder(graph, &dag, frequencies);
+
+    Ok(Ladder::new(path.into_iter().map(|id| graph.word(id)).collect(), dag.stats))
+}
+
+/// Every word a ladder through `via` has to visit, with the start first and
+/// the goal last
+fn waypoints<'w>(start: &'w str, via: &[&'w str], goal: &'w str) -> Vec<&'w str> {
+    let mut words = Vec::with_capacity(via.len() + 2);
+    words.push(start);
+    words.extend_from_slice(via);
+    words.push(goal);
+    words
+}
+
+/// A [`WordGraph`] searched as if the words ruled out by some [`Constraints`]
+/// weren't in it. It offers the same searches as the graph itself; a start or
+/// goal word that has been excluded is reported as
+/// [`SolveError::ExcludedWord`], while words that may only be endpoints are
+/// left in the graph for the ladders that start or end on them.
+pub struct ConstrainedGraph<'w, 'a> {
+    graph: &'w Graph<'a>,
+    removed: BitSet,
+    endpoints_only: Option<BitSet>,
+}
+
+impl<'w, 'a> ConstrainedGraph<'w, 'a> {
+    /// The words to search without for a ladder through `endpoints`: every
+    /// excluded word, and every word that may only be an endpoint apart from
+    /// the ones this ladder actually has
+    fn removed_between(&self, endpoints: &[&str]) -> Cow<'_, BitSet> {
+        let Some(endpoints_only) = &self.endpoints_only else {
+            return Cow::Borrowed(&self.removed)
+        };
+        let mut removed = self.removed.clone();
+        removed.union_with(endpoints_only);
+        for id in endpoints.iter().filter_map(|word| self.graph.id(&normalize(word))) {
+            if !self.removed.contains(id) {
+                removed.remove(id);
+            }
+        }
+
+        Cow::Owned(removed)
+    }
+
+    /// The kind of move that takes `from` to `to`, if they are one move apart
+    pub fn move_kind(&self, from: &str, to: &str) -> Option<MoveKind> {
+        self.graph.move_kind(self.graph.id(from)?, self.graph.id(to)?)
+    }
+
+    /// Finds a shortest ladder from `start` to `goal` with the default
+    /// [`Strategy`], or reports why there is none
+    pub fn shortest_path(&self, start: &str, goal: &str) -> Result<Ladder<'a>, SolveError> {
+        self.shortest_path_with(start, goal, Strategy::default())
+    }
+
+    /// Finds a shortest ladder from `start` to `goal` using the given search
+    /// strategy, or reports why there is none
+    pub fn shortest_path_with(&self, start: &str, goal: &str, strategy: Strategy) -> Result<Ladder<'a>, SolveError> {
+        let removed = self.removed_between(&[start, goal]);
+        find_shortest_path(Subgraph::without(self.graph, &removed), start, goal, strategy)
+            .map(|(words, stats)| Ladder::new(words, stats))
+    }
+
+    /// Finds a ladder from `start` to `goal` that visits each of the `via`
+    /// words in order and never visits any word twice
+    pub fn shortest_path_via(
+        &self,
+        start: &str,
+        via: &[&str],
+        goal: &str,
+        strategy: Strategy,
+    ) -> Result<Ladder<'a>, SolveError> {
+        let words = waypoints(start, via, goal);
+        let removed = self.removed_between(&words);
+        find_path_via(Subgraph::without(self.graph, &removed), &words, strategy)
+            .map(|(words, stats)| Ladder::new(words, stats))
+    }
+
+    /// Finds a ladder from `start` to `goal` that visits every one of the
+    /// `visit` words, in whichever order gives the fewest steps in total
+    pub fn shortest_path_visiting(
+        &self,
+        start: &str,
+        visit: &[&str],
+        goal: &str,
+        strategy: Strategy,
+    ) -> Result<Ladder<'a>, SolveError> {
+        let words = waypoints(start, visit, goal);
+        let removed = self.removed_between(&words);
+        find_best_tour(Subgraph::without(self.graph, &removed), &words, strategy)
+            .map(|(words, stats)| Ladder::new(words, stats))
+    }
+
+    /// Finds every shortest ladder from `start` to `goal`
+    pub fn all_shortest_paths(&self, start: &str, goal: &str) -> Result<ShortestLadders<'w, 'a>, SolveError> {
+        let removed = self.removed_between(&[start, goal]);
+        find_all_shortest_paths(Subgraph::without(self.graph, &removed), start, goal)
+            .map(|dag| ShortestLadders::new(self.graph, dag))
+    }
+
+    /// Finds the shortest ladder from `start` to `goal` whose words are the
+    /// most common according to `frequencies`
+    pub fn most_common_path(
+        &self,
+        start: &str,
+        goal: &str,
+        frequencies: &WordFrequencies,
+    ) -> Result<Ladder<'a>, SolveError> {
+        let removed = self.removed_between(&[start, goal]);
+        most_common_path(Subgraph::without(self.graph, &removed), start, goal, frequencies)
+    }
+
+    /// Counts the shortest ladders from `start` to `goal`
+    pub fn count_shortest_paths(&self, start: &str, goal: &str) -> Result<BigUint, SolveError> {
+        let removed = self.removed_between(&[start, goal]);
+        count_shortest_paths(Subgraph::without(self.graph, &removed), start, goal)
+    }
+
+    /// Finds the ladder from `start` to `goal` whose moves cost the least in
+    /// total under `model`
+    pub fn cheapest_path(&self, start: &str, goal: &str, model: &dyn CostModel) -> Result<CheapestLadder<'a>, SolveError> {
+        let removed = self.removed_between(&[start, goal]);
+        find_cheapest_path(Subgraph::without(self.graph, &removed), start, goal, model)
+            .map(|(words, costs, stats)| CheapestLadder::new(Ladder::new(words, stats), costs))
+    }
+
+    /// Finds up to `k` loopless ladders from `start` to `goal`, shortest first
+    pub fn k_shortest_paths(&self, start: &str, goal: &str, k: usize) -> Result<Vec<Ladder<'a>>, SolveError> {
+        let removed = self.removed_between(&[start, goal]);
+        let ladders = find_k_shortest_paths(Subgraph::without(self.graph, &removed), start, goal, k)?;
+
+        Ok(ladders.into_iter().map(|(words, stats)| Ladder::new(words, stats)).collect())
+    }
+
+    /// Picks out the part of the graph given by `scope`, leaving out the
+    /// excluded words
+    pub fn export(&self, scope: ExportScope) -> Result<GraphExport<'a>, SolveError> {
+        let removed = match scope {
+            ExportScope::Whole => self.removed_between(&[]),
+            ExportScope::Component(word) | ExportScope::Neighborhood { word, .. } => self.removed_between(&[word]),
+            ExportScope::ShortestLadders { start, goal } => self.removed_between(&[start, goal]),
+        };
+        export_scope(Subgraph::without(self.graph, &removed), scope)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dict::DICT;
+    use crate::{Tier, TieredDictionary};
+
+    #[test]
+    fn removing_nothing_leaves_the_whole_graph() {
+        let graph = build_graph_from_dict(&DICT, MoveSet::default());
+        let mut removed = BitSet::new(graph.len());
+        assert!(!Subgraph::without(&graph, &removed).is_restricted());
+        removed.insert(graph.id("work").unwrap());
+        assert!(Subgraph::without(&graph, &removed).is_restricted());
+    }
+
+    #[test]
+    fn excluded_words_and_letters_are_left_off_the_ladder() {
+        let graph = WordGraph::from_dict(&DICT);
+        let mut constraints = Constraints::new();
+        constraints.exclude_words(["work", "cord"]).ban_letters("f");
+        let ladder = graph.constrained(&constraints).shortest_path("word", "dork").unwrap();
+        assert_eq!(ladder.words(), ["word", "wore", "dore", "dork"]);
+
+        let excluded = graph.constrained(&constraints).shortest_path("work", "dork");
+        assert_eq!(excluded.unwrap_err(), SolveError::ExcludedWord("work".to_string()));
+    }
+
+    #[test]
+    fn endpoint_only_words_are_only_used_at_the_ends() {
+        let mut tiers = TieredDictionary::new(Dictionary::from_words(["cold", "cord", "ward", "warm"]));
+        tiers.add_tier(&Dictionary::from_words(["card"]), Tier::Obscure);
+        let graph = WordGraph::from_dictionary(tiers.dictionary());
+        let mut constraints = Constraints::new();
+        constraints.restrict_tier(&tiers, Tier::Allowed);
+        let constrained = graph.constrained(&constraints);
+
+        assert_eq!(graph.shortest_path("cold", "warm").unwrap().words(), ["cold", "cord", "card", "ward", "warm"]);
+        assert!(matches!(constrained.shortest_path("cold", "warm"), Err(SolveError::NoPathExists { .. })));
+        assert_eq!(constrained.shortest_path("card", "warm").unwrap().words(), ["card", "ward", "warm"]);
+    }
+}
@@ -0,0 +1,81 @@
+use std::fmt;
+
+/// A JSON value, just enough of one for `--format json` to write its results
+/// and errors with. Objects keep their fields in the order they were given,
+/// so the output always looks the same.
+pub enum Json {
+    Null,
+    Bool(bool),
+    /// A number, already written out in decimal
+    Number(String),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(&'static str, Json)>),
+}
+
+impl Json {
+    /// A number from anything that prints as one
+    pub fn number(n: impl fmt::Display) -> Self {
+        Json::Number(n.to_string())
+    }
+
+    /// A string from anything that prints as one
+    pub fn string(s: impl fmt::Display) -> Self {
+        Json::String(s.to_string())
+    }
+
+    /// An array of strings
+    pub fn strings<I: IntoIterator<Item = S>, S: fmt::Display>(strings: I) -> Self {
+        Json::Array(strings.into_iter().map(Json::string).collect())
+    }
+}
+
+/// Writes `s` as a JSON string, quoted and escaped
+fn write_string(f: &mut fmt::Formatter<'_>, s: &str) -> fmt::Result {
+    f.write_str("\"")?;
+    for c in s.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            c if c.is_control() => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+    f.write_str("\"")
+}
+
+/// Written compactly, on a single line
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Json::Null => f.write_str("null"),
+            Json::Bool(b) => write!(f, "{}", b),
+            Json::Number(n) => f.write_str(n),
+            Json::String(s) => write_string(f, s),
+            Json::Array(items) => {
+                f.write_str("[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(",")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                f.write_str("]")
+            }
+            Json::Object(fields) => {
+                f.write_str("{")?;
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(",")?;
+                    }
+                    write_string(f, key)?;
+                    write!(f, ":{}", value)?;
+                }
+                f.write_str("}")
+            }
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use crate::search::SearchStats;
+
+/// A solved word ladder: the sequence of words leading from the start word
+/// to the goal word, both included, along with how much searching it took
+/// to find it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ladder<'a> {
+    words: Vec<&'a str>,
+    stats: SearchStats,
+}
+
+impl<'a> Ladder<'a> {
+    pub(crate) fn new(words: Vec<&'a str>, stats: SearchStats) -> Self {
+        Ladder { words, stats }
+    }
+
+    /// Every word on the ladder, in order
+    pub fn words(&self) -> &[&'a str] {
+        &self.words
+    }
+
+    /// The first word of the ladder
+    pub fn start(&self) -> &'a str {
+        self.words[0]
+    }
+
+    /// The last word of the ladder
+    pub fn goal(&self) -> &'a str {
+        self.words[self.words.len() - 1]
+    }
+
+    /// The number of single-letter changes needed to climb the ladder, which
+    /// is one less than the number of words on it
+    pub fn steps(&self) -> usize {
+        self.words.len() - 1
+    }
+
+    /// Counters collected by the search that found the ladder
+    pub fn stats(&self) -> SearchStats {
+        self.stats
+    }
+
+    /// Iterates over the words of the ladder in order
+    pub fn iter(&self) -> impl Iterator<Item = &'a str> + '_ {
+        self.words.iter().copied()
+    }
+}
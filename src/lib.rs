@@ -0,0 +1,58 @@
+//! Weavesolve solves word ladder puzzles such as [weaver](https://wordwormdormdork.com):
+//! given a start word and a goal word, find the shortest chain of words
+//! between them where each word differs from the last by a single letter.
+//!
+//! Build a [`WordGraph`] once from a dictionary and query it for as many
+//! ladders as needed:
+//!
+//! ```
+//! use weavesolve::{dict::DICT, WordGraph};
+//!
+//! let graph = WordGraph::from_dict(&DICT);
+//! let ladder = graph.shortest_path("word", "dork")?;
+//! assert_eq!(ladder.words(), ["word", "work", "dork"]);
+//! # Ok::<(), weavesolve::SolveError>(())
+//! ```
+//!
+//! Other word lists can be loaded into a [`Dictionary`] and used in exactly
+//! the same way with [`WordGraph::from_dictionary`].
+
+mod astar;
+mod bidirectional;
+mod bignum;
+mod bitset;
+mod cache;
+mod constraints;
+mod cost;
+mod dag;
+pub mod dict;
+mod dictionary;
+mod dijkstra;
+mod error;
+mod export;
+mod frequency;
+mod graph;
+mod ladder;
+mod moves;
+mod precomputed;
+mod search;
+mod tiers;
+mod tour;
+mod validate;
+mod waypoints;
+mod yen;
+
+pub use crate::bignum::BigUint;
+pub use crate::cache::GraphCache;
+pub use crate::constraints::Constraints;
+pub use crate::cost::{CheapestLadder, CostModel, Move, WeightedMoves};
+pub use crate::dag::{Ladders, ShortestLadders};
+pub use crate::dictionary::Dictionary;
+pub use crate::error::SolveError;
+pub use crate::export::{ExportFormat, ExportScope, GraphExport};
+pub use crate::frequency::WordFrequencies;
+pub use crate::graph::{ConstrainedGraph, WordGraph};
+pub use crate::ladder::Ladder;
+pub use crate::moves::{MoveKind, MoveSet};
+pub use crate::search::{SearchStats, Strategy};
+pub use crate::tiers::{Tier, TieredDictionary};
@@ -1,197 +1,947 @@
-mod dict;
+mod batch;
+mod json;
 
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::iter;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
 
-use clap::Parser;
+use clap::builder::{PossibleValuesParser, RangedU64ValueParser, TypedValueParser};
+use clap::error::ErrorKind;
+use clap::{Args, CommandFactory, Parser, Subcommand};
 use colored::Colorize;
 
-use crate::dict::DICT;
+use weavesolve::{
+    BigUint, CheapestLadder, ConstrainedGraph, Constraints, Dictionary, ExportFormat, ExportScope, GraphCache, Ladder, MoveKind,
+    MoveSet, SolveError, Strategy, Tier, TieredDictionary, WeightedMoves, WordFrequencies, WordGraph,
+};
 
-fn main() {
+use crate::json::Json;
+
+/// Exit code used when a word list given with `--dict`, `--common`,
+/// `--obscure`, `--exclude-file` or `--freq`, or the input of `batch`, can't
+/// be read
+const WORD_LIST_ERROR: u8 = 8;
+
+/// Exit code used when the output of `export` can't be written
+const OUTPUT_ERROR: u8 = 13;
+
+fn main() -> ExitCode {
     let cli = Cli::parse();
-    let graph = build_graph_from_dict(&DICT);
-    let path = find_shortest_path(graph, &cli.start, &cli.stop);
-    print_path(&path, &cli.stop);
+    if cli.command.is_some() && cli.start.is_some() {
+        Cli::command().error(ErrorKind::ArgumentConflict, "words must be given after the subcommand").exit()
+    }
+    let started = Instant::now();
+    let dictionary = match &cli.dict {
+        Some(path) => match read_list(path, cli.format, |path| Dictionary::from_file(path)) {
+            Ok(dictionary) => dictionary,
+            Err(code) => return code,
+        },
+        None => Dictionary::builtin(),
+    };
+    let mut tiers = TieredDictionary::new(dictionary);
+    for (path, tier) in [(&cli.common, Tier::Common), (&cli.obscure, Tier::Obscure)] {
+        if let Some(path) = path {
+            match read_list(path, cli.format, |path| Dictionary::from_file(path)) {
+                Ok(words) => tiers.add_tier(&words, tier),
+                Err(code) => return code,
+            };
+        }
+    }
+
+    let mut constraints = Constraints::new();
+    constraints.exclude_words(&cli.exclude);
+    if let Some(path) = &cli.exclude_file {
+        match read_list(path, cli.format, |path| Dictionary::from_file(path)) {
+            Ok(excluded) => constraints.exclude_words(excluded.words()),
+            Err(code) => return code,
+        };
+    }
+    if let Some(letters) = &cli.ban_letters {
+        constraints.ban_letters(letters);
+    }
+    if let Some(tier) = cli.tier {
+        constraints.restrict_tier(&tiers, tier);
+    }
+    let frequencies = match &cli.freq {
+        Some(path) => match read_list(path, cli.format, |path| WordFrequencies::from_file(path)) {
+            Ok(frequencies) => frequencies,
+            Err(code) => return code,
+        },
+        None => WordFrequencies::default(),
+    };
+
+    let lines = match &cli.command {
+        Some(Command::Batch(args)) => match read_list(&args.input, cli.format, batch::read_lines) {
+            Ok(lines) => lines,
+            Err(code) => return code,
+        },
+        _ => Vec::new(),
+    };
+    let words: Vec<&String> = match &cli.command {
+        Some(Command::Export(args)) => args.start.iter().chain(&args.goal).collect(),
+        Some(Command::Batch(_)) => {
+            lines.iter().flat_map(batch::Line::words).chain(&cli.via).chain(&cli.visit).collect()
+        }
+        None => cli.start.iter().chain(&cli.stop).chain(&cli.via).chain(&cli.visit).collect(),
+    };
+    // unless the moves can change the length of a word, only the words as
+    // long as the ones we were given can be on the ladder, and without any
+    // words at all every word is exported
+    let moves = MoveSet::new(&cli.moves);
+    let cache = if cli.no_cache { None } else { cli.cache_dir.clone().map(GraphCache::new).or_else(GraphCache::user) };
+    let graph = match (&cache, moves.changes_length() || words.is_empty()) {
+        (Some(cache), true) => WordGraph::from_dictionary_cached(tiers.dictionary(), moves, cache),
+        (None, true) => WordGraph::from_dictionary_with_moves(tiers.dictionary(), moves),
+        (cache, false) => {
+            // a batch can give the same few lengths thousands of times over
+            let mut lengths: Vec<usize> = words.iter().map(|word| word.trim().chars().count()).collect();
+            lengths.sort_unstable();
+            lengths.dedup();
+            match cache {
+                Some(cache) => WordGraph::for_lengths_cached(tiers.dictionary(), &lengths, moves, cache),
+                None => WordGraph::for_lengths_with_moves(tiers.dictionary(), &lengths, moves),
+            }
+        }
+    };
+    let graph = graph.constrained(&constraints);
+    let built = started.elapsed();
+
+    let output = Output { cli: &cli, graph: &graph, tiers: &tiers, built };
+    let (start, stop) = match (&cli.command, &cli.start, &cli.stop) {
+        (Some(Command::Export(args)), _, _) => return export(&graph, &cli, args),
+        (Some(Command::Batch(args)), _, _) => return batch::solve(&output, &frequencies, &lines, args.jobs),
+        (None, Some(start), Some(stop)) => (start.as_str(), stop.as_str()),
+        _ => unreachable!("clap requires both words unless there is a subcommand"),
+    };
+    let started = Instant::now();
+    let result = query(&graph, &cli, &frequencies, start, stop);
+    output.print_header();
+    output.print(&result, [start, stop], started.elapsed());
+    match result {
+        Ok(_) => ExitCode::SUCCESS,
+        Err(err) => ExitCode::from(exit_code(&err)),
+    }
 }
 
-/// A helper function for printing the solution path nicely
-fn print_path(path: &Vec<&str>, stop: &str) {
-    for &word in path.iter() {
-        for (cword, cstop) in word.chars().zip(stop.chars()) {
-            if cword == cstop {
-                print!("{}", format!("{}", cword).green());
-            } else {
-                print!("{}", format!("{}", cword));
+/// Reads a word list with `load`, reporting the exit code to stop with if it
+/// can't be read
+fn read_list<T>(path: &Path, format: Format, load: fn(&Path) -> io::Result<T>) -> Result<T, ExitCode> {
+    load(path).map_err(|err| {
+        let message = format!("could not read word list {}: {}", path.display(), err);
+        match format {
+            Format::Text | Format::Csv => eprintln!("{}", message),
+            Format::Json => {
+                let error = Json::Object(vec![
+                    ("kind", Json::string("word-list")),
+                    ("message", Json::String(message)),
+                    ("exit_code", Json::number(WORD_LIST_ERROR)),
+                    ("path", Json::string(path.display())),
+                ]);
+                println!("{}", Json::Object(vec![("error", error)]));
             }
         }
-        if word == stop {
-            break
-        } else {
-            print!(" -> ");
+        ExitCode::from(WORD_LIST_ERROR)
+    })
+}
+
+/// What a query found, kept until it is printed in the chosen `--format`
+struct Report<'a> {
+    /// The name of the search that found it, as printed by `--stats`
+    search: String,
+    /// How many words the search expanded, or states for `--cheapest`, if it
+    /// counts them at all
+    expanded: Option<usize>,
+    found: Found<'a>,
+}
+
+/// The answer to each kind of query
+enum Found<'a> {
+    /// A single ladder
+    Ladder(Ladder<'a>),
+    /// The cheapest ladder, along with what each of its moves cost
+    Cheapest(CheapestLadder<'a>),
+    /// Every shortest ladder up to `--limit`, and whether there were more
+    All { ladders: Vec<Ladder<'a>>, truncated: bool },
+    /// The `k` shortest loopless ladders, shortest first
+    KShortest(Vec<Ladder<'a>>),
+    /// How many shortest ladders there are
+    Count(BigUint),
+}
+
+impl<'a> Report<'a> {
+    /// A report of a single ladder found by `search`
+    fn ladder(search: impl fmt::Display, path: Ladder<'a>) -> Self {
+        Report { search: search.to_string(), expanded: Some(path.stats().expanded), found: Found::Ladder(path) }
+    }
+}
+
+/// Answers the query the options ask for about the ladders from `start` to
+/// `stop`
+fn query<'a>(
+    graph: &ConstrainedGraph<'_, 'a>,
+    cli: &Cli,
+    frequencies: &WordFrequencies,
+    start: &str,
+    stop: &str,
+) -> Result<Report<'a>, SolveError> {
+    if cli.count {
+        count(graph, start, stop)
+    } else if cli.all {
+        solve_all(graph, cli, start, stop)
+    } else if let Some(k) = cli.k {
+        solve_k(graph, start, stop, k)
+    } else if cli.prefer_common {
+        solve_common(graph, start, stop, frequencies)
+    } else if cli.cheapest {
+        solve_cheapest(graph, cli, start, stop)
+    } else if !cli.via.is_empty() {
+        solve_via(graph, cli, start, stop)
+    } else if !cli.visit.is_empty() {
+        solve_visit(graph, cli, start, stop)
+    } else {
+        solve(graph, cli, start, stop)
+    }
+}
+
+/// Finds the `k` shortest loopless ladders
+fn solve_k<'a>(
+    graph: &ConstrainedGraph<'_, 'a>,
+    start: &str,
+    stop: &str,
+    k: usize,
+) -> Result<Report<'a>, SolveError> {
+    let ladders = graph.k_shortest_paths(start, stop, k)?;
+    let expanded = ladders.last().map_or(0, |path| path.stats().expanded);
+
+    Ok(Report { search: "k-shortest".to_string(), expanded: Some(expanded), found: Found::KShortest(ladders) })
+}
+
+/// Finds a single ladder that passes through every `--via` word in order
+fn solve_via<'a>(
+    graph: &ConstrainedGraph<'_, 'a>,
+    cli: &Cli,
+    start: &str,
+    stop: &str,
+) -> Result<Report<'a>, SolveError> {
+    let via: Vec<&str> = cli.via.iter().map(String::as_str).collect();
+    let path = graph.shortest_path_via(start, &via, stop, cli.strategy)?;
+
+    Ok(Report::ladder(cli.strategy, path))
+}
+
+/// Finds a single ladder that passes through every `--visit` word, in the
+/// order that makes it shortest
+fn solve_visit<'a>(
+    graph: &ConstrainedGraph<'_, 'a>,
+    cli: &Cli,
+    start: &str,
+    stop: &str,
+) -> Result<Report<'a>, SolveError> {
+    let visit: Vec<&str> = cli.visit.iter().map(String::as_str).collect();
+    let path = graph.shortest_path_visiting(start, &visit, stop, cli.strategy)?;
+
+    Ok(Report::ladder(cli.strategy, path))
+}
+
+/// Finds the cheapest ladder under the `--*-cost` options
+fn solve_cheapest<'a>(
+    graph: &ConstrainedGraph<'_, 'a>,
+    cli: &Cli,
+    start: &str,
+    stop: &str,
+) -> Result<Report<'a>, SolveError> {
+    let model = WeightedMoves { base: cli.move_cost, vowel: cli.vowel_cost, repeat: cli.repeat_cost };
+    let cheapest = graph.cheapest_path(start, stop, &model)?;
+    let expanded = cheapest.ladder().stats().expanded;
+
+    Ok(Report { search: "dijkstra".to_string(), expanded: Some(expanded), found: Found::Cheapest(cheapest) })
+}
+
+/// Finds the shortest ladder made of the most common words
+fn solve_common<'a>(
+    graph: &ConstrainedGraph<'_, 'a>,
+    start: &str,
+    stop: &str,
+    frequencies: &WordFrequencies,
+) -> Result<Report<'a>, SolveError> {
+    let path = graph.most_common_path(start, stop, frequencies)?;
+
+    Ok(Report::ladder("shortest-ladder", path))
+}
+
+/// Counts how many shortest ladders there are
+fn count<'a>(graph: &ConstrainedGraph<'_, 'a>, start: &str, stop: &str) -> Result<Report<'a>, SolveError> {
+    let count = graph.count_shortest_paths(start, stop)?;
+
+    Ok(Report { search: "shortest-ladder".to_string(), expanded: None, found: Found::Count(count) })
+}
+
+/// Finds a single shortest ladder
+fn solve<'a>(graph: &ConstrainedGraph<'_, 'a>, cli: &Cli, start: &str, stop: &str) -> Result<Report<'a>, SolveError> {
+    let path = graph.shortest_path_with(start, stop, cli.strategy)?;
+
+    Ok(Report::ladder(cli.strategy, path))
+}
+
+/// Finds every shortest ladder, up to `--limit` of them
+fn solve_all<'a>(
+    graph: &ConstrainedGraph<'_, 'a>,
+    cli: &Cli,
+    start: &str,
+    stop: &str,
+) -> Result<Report<'a>, SolveError> {
+    let shortest = graph.all_shortest_paths(start, stop)?;
+    // asking for one more than the limit tells us whether we stopped early
+    let mut ladders: Vec<Ladder> = shortest.iter().take(cli.limit.saturating_add(1)).collect();
+    let truncated = ladders.len() > cli.limit;
+    ladders.truncate(cli.limit);
+    let expanded = shortest.stats().expanded;
+
+    Ok(Report {
+        search: "shortest-ladder".to_string(),
+        expanded: Some(expanded),
+        found: Found::All { ladders, truncated },
+    })
+}
+
+/// Prints a report as text: the ladders one per line, and with `--stats`, how
+/// many words the search expanded on stderr
+fn print_report(report: &Report, cli: &Cli, printer: &Printer) {
+    let mut expanded = "words";
+    match &report.found {
+        Found::Ladder(path) => printer.print(path),
+        Found::Cheapest(cheapest) => {
+            let path = cheapest.ladder();
+            printer.print(path);
+            for (step, cost) in path.words().windows(2).zip(cheapest.costs()) {
+                println!("  {} -> {}: {}", step[0], step[1], cost);
+            }
+            println!("total cost: {}", cheapest.cost());
+            expanded = "states";
+        }
+        Found::All { ladders, truncated } => {
+            for path in ladders {
+                printer.print(path);
+            }
+            if *truncated {
+                eprintln!("stopped after {} ladders; use --limit to see more", cli.limit);
+            }
+        }
+        Found::KShortest(ladders) => {
+            for path in ladders {
+                print!("{:>3}: ", path.steps());
+                printer.print(path);
+            }
         }
+        Found::Count(count) => println!("{}", count),
+    }
+    if let Some(count) = report.expanded.filter(|_| cli.stats) {
+        eprintln!("{} search expanded {} {}", report.search, count, expanded);
     }
-    println!();
 }
 
-/// Defines the CLI for Weavesolve
-#[derive(Parser, Debug)]
-struct Cli {
-    /// Starting word
-    start: String,
+/// Prints the answers to queries in the chosen `--format`
+struct Output<'o> {
+    cli: &'o Cli,
+    graph: &'o ConstrainedGraph<'o, 'o>,
+    tiers: &'o TieredDictionary,
+    /// How long building the graph took
+    built: Duration,
+}
 
-    /// Ending word
-    stop: String,
+impl Output<'_> {
+    /// Prints what comes before the first answer, which is the header row
+    /// for `--format csv` and nothing otherwise
+    fn print_header(&self) {
+        if self.cli.format == Format::Csv {
+            println!("{}", CSV_HEADER.join(","));
+        }
+    }
+
+    /// Prints what a query for the ladders between `words` found, or why it
+    /// failed. `searched` is how long the query took.
+    fn print(&self, result: &Result<Report, SolveError>, words: [&str; 2], searched: Duration) {
+        match (result, self.cli.format) {
+            (Ok(report), Format::Text) => {
+                let printer = Printer {
+                    tiers: self.cli.show_tiers.then_some(self.tiers),
+                    moves: self.cli.show_moves.then_some(self.graph),
+                };
+                print_report(report, self.cli, &printer);
+            }
+            (Ok(report), Format::Json) => {
+                println!("{}", report_json(report, words, self.graph, self.tiers, [self.built, searched]));
+            }
+            (Ok(report), Format::Csv) => {
+                for row in report_csv(report, words) {
+                    println!("{}", csv_row(&row));
+                }
+            }
+            (Err(err), Format::Text) => eprintln!("{}", err),
+            (Err(err), Format::Json) => {
+                let [start, stop] = words.map(Json::string);
+                println!("{}", Json::Object(vec![("start", start), ("goal", stop), ("error", error_json(err))]));
+            }
+            (Err(err), Format::Csv) => {
+                let mut row = empty_row(words);
+                row[6] = error_kind(err).to_string();
+                println!("{}", csv_row(&row));
+            }
+        }
+    }
 }
 
-/// We can represent the word ladder data as a `HashMap` keyed by strings
-/// with `Vec`s of strings as values
-type Graph<'g> = HashMap<&'g str, Vec::<&'g str>>;
+/// The columns of `--format csv`. Each ladder gets a row of its own, with its
+/// words separated by spaces in the `path` column; `cost` is only filled in by
+/// `--cheapest`, `count` only by `--count`, and `error` only when there is no
+/// answer, with the same kinds of error as `--format json`.
+const CSV_HEADER: [&str; 7] = ["start", "goal", "length", "path", "cost", "count", "error"];
+
+/// A row of `--format csv` for the ladders between `words`, with nothing in
+/// the other columns yet
+fn empty_row([start, stop]: [&str; 2]) -> [String; 7] {
+    let mut row = CSV_HEADER.map(|_| String::new());
+    row[0] = start.to_string();
+    row[1] = stop.to_string();
+    row
+}
 
-/// A simple wrapper around the rust VecDeque type, in order to match the
-/// `enqueue` and `dequeue` functions used in the breadth-first search
-/// pseudocode
-struct Queue<T> {
-    queue: VecDeque<T>,
+/// The rows of `--format csv` for a report on the ladders between `words`
+fn report_csv(report: &Report, words: [&str; 2]) -> Vec<[String; 7]> {
+    let ladder = |path: &Ladder| {
+        let mut row = empty_row(words);
+        row[2] = path.steps().to_string();
+        row[3] = path.words().join(" ");
+        row
+    };
+    match &report.found {
+        Found::Ladder(path) => vec![ladder(path)],
+        Found::Cheapest(cheapest) => {
+            let mut row = ladder(cheapest.ladder());
+            row[4] = cheapest.cost().to_string();
+            vec![row]
+        }
+        Found::All { ladders, .. } | Found::KShortest(ladders) => ladders.iter().map(ladder).collect(),
+        Found::Count(count) => {
+            let mut row = empty_row(words);
+            row[5] = count.to_string();
+            vec![row]
+        }
+    }
 }
 
-impl<T> Queue<T> {
-    fn new() -> Self {
-        Queue { queue: VecDeque::new() }
-    } 
+/// Joins the fields of a CSV row, quoting the ones that need it
+fn csv_row(fields: &[String]) -> String {
+    let quoted: Vec<String> = fields
+        .iter()
+        .map(|field| {
+            if field.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.clone()
+            }
+        })
+        .collect();
+    quoted.join(",")
+}
 
-    fn enqueue(&mut self, val: T) {
-        self.queue.push_back(val);
+/// Turns a report on the ladders between `words` into a single JSON object,
+/// with the same fields for every kind of query plus the ones that hold its
+/// answer. `timing` is how long building the graph and then searching it took.
+fn report_json(
+    report: &Report,
+    [start, stop]: [&str; 2],
+    graph: &ConstrainedGraph,
+    tiers: &TieredDictionary,
+    timing: [Duration; 2],
+) -> Json {
+    let mut fields = vec![
+        ("start", Json::string(start)),
+        ("goal", Json::string(stop)),
+        ("strategy", Json::string(&report.search)),
+    ];
+    match &report.found {
+        Found::Ladder(path) => fields.extend(ladder_json(path, graph, tiers)),
+        Found::Cheapest(cheapest) => {
+            fields.extend(ladder_json(cheapest.ladder(), graph, tiers));
+            fields.push(("costs", Json::Array(cheapest.costs().iter().map(Json::number).collect())));
+            fields.push(("cost", Json::number(cheapest.cost())));
+        }
+        Found::All { ladders, truncated } => {
+            let ladders = ladders.iter().map(|path| Json::Object(ladder_json(path, graph, tiers))).collect();
+            fields.push(("ladders", Json::Array(ladders)));
+            fields.push(("truncated", Json::Bool(*truncated)));
+        }
+        Found::KShortest(ladders) => {
+            let ladders = ladders.iter().map(|path| Json::Object(ladder_json(path, graph, tiers))).collect();
+            fields.push(("ladders", Json::Array(ladders)));
+        }
+        Found::Count(count) => fields.push(("count", Json::number(count))),
     }
+    fields.push(("expanded", report.expanded.map_or(Json::Null, Json::number)));
+    let [build, search] = timing.map(|time| Json::Number(format!("{:.3}", time.as_secs_f64() * 1000.0)));
+    fields.push(("timing", Json::Object(vec![("build_ms", build), ("search_ms", search)])));
 
-    fn dequeue(&mut self) -> Option<T> {
-        self.queue.pop_front()
+    Json::Object(fields)
+}
+
+/// The fields describing a single ladder: its words, its number of steps, the
+/// kind of each move and the tier of each word
+fn ladder_json(path: &Ladder, graph: &ConstrainedGraph, tiers: &TieredDictionary) -> Vec<(&'static str, Json)> {
+    let moves = path.words().windows(2).map(|step| graph.move_kind(step[0], step[1]).map_or(Json::Null, Json::string));
+    let tiers = path.iter().map(|word| tiers.tier(word).map_or(Json::Null, Json::string));
+    vec![
+        ("path", Json::strings(path.iter())),
+        ("length", Json::number(path.steps())),
+        ("moves", Json::Array(moves.collect())),
+        ("tiers", Json::Array(tiers.collect())),
+    ]
+}
+
+/// The error object printed by `--format json`. Every error has a `kind`, a
+/// `message` and the `exit_code` the process stops with, and then the words
+/// it is about: a `word`, a `start` and a `goal`, or the `problems` found with
+/// the input, each an error object of its own.
+fn error_json(err: &SolveError) -> Json {
+    let mut fields = vec![
+        ("kind", Json::string(error_kind(err))),
+        ("message", Json::string(err)),
+        ("exit_code", Json::number(exit_code(err))),
+    ];
+    match err {
+        SolveError::InvalidCharacters(word)
+        | SolveError::UnknownStartWord(word)
+        | SolveError::UnknownGoalWord(word)
+        | SolveError::UnknownWaypoint(word)
+        | SolveError::RepeatedWaypoint(word)
+        | SolveError::ExcludedWord(word) => fields.push(("word", Json::string(word))),
+        SolveError::LengthMismatch { start, goal }
+        | SolveError::NoPathExists { start, goal }
+        | SolveError::WaypointBlocked { start, goal } => {
+            fields.push(("start", Json::string(start)));
+            fields.push(("goal", Json::string(goal)));
+        }
+        SolveError::InvalidInput(problems) => {
+            fields.push(("problems", Json::Array(problems.iter().map(error_json).collect())));
+        }
     }
 
-    fn is_empty(&self) -> bool {
-        self.queue.is_empty()
+    Json::Object(fields)
+}
+
+/// The name of each kind of error in `--format json`
+fn error_kind(err: &SolveError) -> &'static str {
+    match err {
+        SolveError::NoPathExists { .. } => "no-path",
+        SolveError::UnknownStartWord(_) => "unknown-start-word",
+        SolveError::UnknownGoalWord(_) => "unknown-goal-word",
+        SolveError::LengthMismatch { .. } => "length-mismatch",
+        SolveError::InvalidCharacters(_) => "invalid-characters",
+        SolveError::InvalidInput(_) => "invalid-input",
+        SolveError::ExcludedWord(_) => "excluded-word",
+        SolveError::UnknownWaypoint(_) => "unknown-waypoint",
+        SolveError::RepeatedWaypoint(_) => "repeated-waypoint",
+        SolveError::WaypointBlocked { .. } => "waypoint-blocked",
     }
 }
 
-/// Determines whether two strings of the same length
-/// differ by only one character. Will behave unexpectedly
-/// if the strings are different lengths because of the use
-/// of `zip`. No check is performed because this problem
-/// is solved by only having a dictionary of 4-letter words
-/// to reference.
-fn is_one_char_diff(s1: &str, s2: &str) -> bool {
-    let iter = s1.chars().zip(s2.chars());
-    let mut counter = 0;
+/// Writes the part of the graph picked out by the `export` options, with a
+/// shortest ladder highlighted when both words are given
+fn export(graph: &ConstrainedGraph, cli: &Cli, args: &ExportArgs) -> ExitCode {
+    let scope = match (args.start.as_deref(), args.goal.as_deref(), args.hops) {
+        (Some(start), Some(goal), _) if args.ladders => ExportScope::ShortestLadders { start, goal },
+        (Some(word), _, Some(hops)) => ExportScope::Neighborhood { word, hops },
+        (Some(word), _, None) if args.component => ExportScope::Component(word),
+        _ => ExportScope::Whole,
+    };
+    let exported = graph.export(scope).and_then(|mut exported| {
+        if let (Some(start), Some(goal)) = (&args.start, &args.goal) {
+            exported.highlight(&graph.shortest_path_with(start, goal, cli.strategy)?);
+        }
+        Ok(exported)
+    });
+    let exported = match exported {
+        Ok(exported) => exported,
+        Err(err) => {
+            match cli.format {
+                Format::Text | Format::Csv => eprintln!("{}", err),
+                Format::Json => {
+                    let words = [("start", &args.start), ("goal", &args.goal)];
+                    let mut fields: Vec<_> =
+                        words.into_iter().filter_map(|(key, word)| Some((key, Json::string(word.as_ref()?)))).collect();
+                    fields.push(("error", error_json(&err)));
+                    println!("{}", Json::Object(fields));
+                }
+            }
+            return ExitCode::from(exit_code(&err))
+        }
+    };
 
-    for (c1, c2) in iter {
-        if c1 != c2 {
-            counter += 1;
+    let written = match &args.output {
+        Some(path) => File::create(path).and_then(|file| {
+            let mut out = BufWriter::new(file);
+            exported.write(args.to, &mut out)?;
+            out.flush()
+        }),
+        None => {
+            let mut out = BufWriter::new(io::stdout().lock());
+            exported.write(args.to, &mut out).and_then(|()| out.flush())
+        }
+    };
+    match written {
+        // a reader such as `head` that stops early isn't a failure to write
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) if err.kind() == io::ErrorKind::BrokenPipe => ExitCode::SUCCESS,
+        Err(err) => {
+            let output = args.output.as_deref().map_or("the graph".into(), Path::to_string_lossy);
+            let message = format!("could not write {}: {}", output, err);
+            match (cli.format, &args.output) {
+                (Format::Json, Some(path)) => {
+                    let error = Json::Object(vec![
+                        ("kind", Json::string("output")),
+                        ("message", Json::String(message)),
+                        ("exit_code", Json::number(OUTPUT_ERROR)),
+                        ("path", Json::string(path.display())),
+                    ]);
+                    println!("{}", Json::Object(vec![("error", error)]));
+                }
+                _ => eprintln!("{}", message),
+            }
+            ExitCode::from(OUTPUT_ERROR)
         }
     }
+}
 
-    if counter == 1 {
-        true
-    } else {
-        false
-    }
-}
-
-/// Takes a dictionary of words and then builds the graph of words
-/// that are connected one they differ by a single letter only. 
-/// Because we are using a `HashMap` to represent a graph, we make
-/// sure to symmetrically insert nodes, and then we only have to examine 
-/// half of all possible pairs of words.
-fn build_graph_from_dict<'a>(dict: &[&'a str]) -> Graph<'a> {
-    let mut graph: Graph = HashMap::new();
-    for i in 0..dict.len() {
-        // because we will insert connections symmetrically, we only need
-        // to check pairs from `i + 1` forward
-        for j in i + 1..dict.len() {
-            if is_one_char_diff(dict[i], dict[j]) {
-                graph
-                    .entry(dict[i])
-                    .and_modify(|connections| connections.push(dict[j])) // if the entry already exists, we want to push the next match
-                    .or_insert(vec![dict[j]]); // otherwise we make the entry
-                
-                graph
-                    .entry(dict[j])
-                    .and_modify(|connections| connections.push(dict[i]))
-                    .or_insert(vec![dict[i]]);
-            }
-        }
-    }
-
-    graph
-}
-
-/// A general breadth-first search algorithm defined on our `Graph` type. 
-/// The most significant deviation from this pseudocode is that we cannot easily
-/// attach some notion of a parent to our graph nodes. Presumably, this implementation
-/// assumes a more custom graph type that can hold this additional data in each node.
-/// Instead, I simply make a new `HashMap` where each entry points to that word's parent
-/// string. We are guaranteed to not overwrite this value at any point because a breadth-first
-/// search such as this is constructing a tree where each node has exactly one parent.
-/// 
-/// Pseudocode to be translated into Rust code
-/// 
-/// ```
-///     procedure BFS(G, root) is
-///         let Q be a queue
-///         label root as explored
-///         Q.enqueue(root)
-///         while Q is not empty do
-///             v = Q.dequeue()
-///             if v is the goal then
-///                 return v
-///             for all edges from v to w in G.adjacentEdges(v) do
-///                 if w is n ot labeled as explored then
-///                     label w as explored
-///                     w.parent = v 
-///                     Q.enqueue(w)
-/// ```
-fn bfs<'g>(graph: Graph<'g>, root: &'g str, goal: &'g str) -> (&'g str, HashMap<&'g str, &'g str>) {
-    let mut q = Queue::new();
-    let mut visited = HashSet::new();
-    let mut parent_map = HashMap::new();
-    visited.insert(root);
-    q.enqueue(root);
-    while !q.is_empty() {
-        let v = q.dequeue().unwrap(); // `unwrap()` is safe here since we checked not empty
-        if v == goal {
-            return (v, parent_map)
-        }
-        if let None = graph.get(v) { 
-            eprintln!("{} is not a valid word!", v); 
-            std::process::exit(1); 
-        }
-        
-        for &entry in graph[v].iter() {
-            if let None = visited.get(entry) {
-                visited.insert(entry);
-                parent_map.insert(entry, v);
-                q.enqueue(entry);
-            }
-        }
-    }
-    
-    // Rust doesn't love something like a `while` loop that will eventually return from within
-    // so we mark the end of the function here as `unreachable!()`
-    unreachable!()
-}
-
-/// Use our `bfs` implementation to get the result we actually want: the solution path. 
-/// This simply requires taking the parent map, the end word, and the start word, and
-/// walking backward from there to construct the actual solution path. Then we simply reverse
-/// the result of that to have the path in the order we want.
-fn find_shortest_path<'g>(graph: Graph<'g>, start: &'g str, end: &'g str) -> Vec<&'g str> {
-    let (sol, parent_map) = bfs(graph, start, end);
-    let mut ptr = sol;
-    let mut path = Vec::new();
-    while ptr != start {
-        path.push(ptr);
-        ptr = parent_map[ptr];
-    }
-    path.push(start);
-    let path: Vec<&str> = path.into_iter().rev().collect();
-    
-    path
-}
\ No newline at end of file
+/// Each way of failing to find a ladder gets its own exit code, so that
+/// scripts can tell them apart without parsing stderr. Exit code 2 is left
+/// to `clap` for usage errors.
+fn exit_code(err: &SolveError) -> u8 {
+    match err {
+        SolveError::NoPathExists { .. } => 1,
+        SolveError::UnknownStartWord(_) => 3,
+        SolveError::UnknownGoalWord(_) => 4,
+        SolveError::LengthMismatch { .. } => 5,
+        SolveError::InvalidCharacters(_) => 6,
+        SolveError::InvalidInput(_) => 7,
+        SolveError::ExcludedWord(_) => 9,
+        SolveError::UnknownWaypoint(_) => 10,
+        SolveError::RepeatedWaypoint(_) => 11,
+        SolveError::WaypointBlocked { .. } => 12,
+    }
+}
+
+/// How results and errors are printed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum Format {
+    /// Ladders one per line, with errors on stderr
+    #[default]
+    Text,
+    /// A single JSON object on stdout, holding either the result or an error
+    Json,
+    /// Comma-separated values on stdout, with a header row and then a row
+    /// for each ladder, or for the error
+    Csv,
+}
+
+impl Format {
+    /// Every format, in the order they are listed in help text
+    const ALL: [Format; 3] = [Format::Text, Format::Json, Format::Csv];
+
+    /// The name used for the format on the command line
+    fn name(self) -> &'static str {
+        match self {
+            Format::Text => "text",
+            Format::Json => "json",
+            Format::Csv => "csv",
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Parses an option that takes one of `values` by the name `name` gives it,
+/// listing every name in help text and in the error for any other value
+fn one_of<T>(values: &'static [T], name: fn(T) -> &'static str) -> impl TypedValueParser<Value = T>
+where
+    T: Copy + Send + Sync + 'static,
+{
+    PossibleValuesParser::new(values.iter().map(|&value| name(value)))
+        .try_map(move |chosen| values.iter().copied().find(|&value| name(value) == chosen).ok_or(chosen))
+}
+
+/// Prints ladders nicely, with the letters that already match the ending word
+/// in green, with `--show-tiers`, the tier of each word after it and, with
+/// `--show-moves`, the kind of each move on its arrow
+struct Printer<'p> {
+    tiers: Option<&'p TieredDictionary>,
+    moves: Option<&'p ConstrainedGraph<'p, 'p>>,
+}
+
+impl Printer<'_> {
+    /// A helper function for printing the solution path nicely
+    fn print(&self, path: &Ladder) {
+        for (i, word) in path.iter().enumerate() {
+            // words can be longer than the ending word when the moves can
+            // change their length, so the ending word is padded to match
+            let stop = path.goal().chars().map(Some).chain(iter::repeat(None));
+            for (cword, cstop) in word.chars().zip(stop) {
+                if Some(cword) == cstop {
+                    print!("{}", cword.to_string().green());
+                } else {
+                    print!("{}", cword);
+                }
+            }
+            if let Some(tier) = self.tiers.and_then(|tiers| tiers.tier(word)) {
+                print!(" {}", format!("({})", tier).dimmed());
+            }
+            if word == path.goal() {
+                break
+            }
+            let kind = self.moves.zip(path.words().get(i + 1)).and_then(|(graph, next)| graph.move_kind(word, next));
+            match kind {
+                Some(kind) => print!(" -{}-> ", kind.to_string().dimmed()),
+                None => print!(" -> "),
+            }
+        }
+        println!();
+    }
+}
+
+/// Defines the CLI for Weavesolve
+#[derive(Parser, Debug)]
+#[command(subcommand_negates_reqs = true)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Starting word
+    #[arg(required = true)]
+    start: Option<String>,
+
+    /// Ending word
+    #[arg(required = true)]
+    stop: Option<String>,
+
+    // every option is global, so that it can be given to the subcommands too
+
+    /// Newline-separated word list to use instead of the built-in dictionary
+    #[arg(long, value_name = "PATH", global = true)]
+    dict: Option<PathBuf>,
+
+    /// Newline-separated list of common words, added to the dictionary in
+    /// the common tier
+    #[arg(long, value_name = "PATH", global = true)]
+    common: Option<PathBuf>,
+
+    /// Newline-separated list of obscure words, added to the dictionary in
+    /// the obscure tier
+    #[arg(long, value_name = "PATH", global = true)]
+    obscure: Option<PathBuf>,
+
+    /// Directory to keep built word graphs in, instead of the user's cache
+    /// directory
+    #[arg(long, value_name = "PATH", global = true)]
+    cache_dir: Option<PathBuf>,
+
+    /// Build the word graph from scratch, without reading or saving a cached
+    /// one
+    #[arg(long, global = true, conflicts_with = "cache_dir")]
+    no_cache: bool,
+
+    /// Only use words of this tier or a more common one between the starting
+    /// and ending words
+    #[arg(
+        long,
+        global = true,
+        value_parser = one_of(&Tier::ALL, Tier::name),
+    )]
+    tier: Option<Tier>,
+
+    /// Print the tier of each word on the ladder after it
+    #[arg(long, global = true)]
+    show_tiers: bool,
+
+    /// Print the kind of each move on the ladder on its arrow
+    #[arg(long, global = true)]
+    show_moves: bool,
+
+    /// The kinds of move allowed from one word to the next, separated by
+    /// commas
+    #[arg(
+        long,
+        global = true,
+        value_delimiter = ',',
+        default_value = "substitute",
+        value_parser = one_of(&MoveKind::ALL, MoveKind::name),
+    )]
+    moves: Vec<MoveKind>,
+
+    /// Search algorithm used to find the ladder
+    #[arg(
+        long,
+        global = true,
+        default_value_t = Strategy::default(),
+        value_parser = one_of(&Strategy::ALL, Strategy::name),
+    )]
+    strategy: Strategy,
+
+    /// Report how many words the search expanded, on stderr
+    #[arg(long, global = true)]
+    stats: bool,
+
+    /// How to print the result, or the error if there is no result
+    #[arg(
+        long,
+        global = true,
+        default_value_t = Format::default(),
+        value_parser = one_of(&Format::ALL, Format::name),
+    )]
+    format: Format,
+
+    /// Print every shortest ladder instead of just one
+    #[arg(long, global = true)]
+    all: bool,
+
+    /// The most ladders to print with --all
+    #[arg(long, value_name = "N", default_value_t = 1000, requires = "all", global = true)]
+    limit: usize,
+
+    /// Print only how many shortest ladders there are
+    #[arg(long, global = true, conflicts_with = "all")]
+    count: bool,
+
+    /// Print the N shortest ladders that never repeat a word, including
+    /// longer ones once the shortest run out
+    #[arg(
+        long = "k",
+        value_name = "N",
+        value_parser = RangedU64ValueParser::<usize>::new().range(1..),
+        conflicts_with_all = ["all", "count"],
+        global = true,
+    )]
+    k: Option<usize>,
+
+    /// A word the ladder must pass through; may be given more than once, and
+    /// the words are visited in the order given
+    #[arg(long, value_name = "WORD", global = true, conflicts_with_all = ["all", "count", "k"])]
+    via: Vec<String>,
+
+    /// Words the ladder must pass through, separated by commas, in whichever
+    /// order makes the ladder shortest
+    #[arg(
+        long,
+        value_name = "WORDS",
+        value_delimiter = ',',
+        global = true,
+        conflicts_with_all = ["all", "count", "k", "via"],
+    )]
+    visit: Vec<String>,
+
+    /// Word-frequency list, with a word and its count on each line
+    #[arg(long, value_name = "PATH", global = true)]
+    freq: Option<PathBuf>,
+
+    /// Of all the shortest ladders, print the one made of the most common
+    /// words according to --freq
+    #[arg(long, global = true, requires = "freq", conflicts_with_all = ["all", "count", "k", "via", "visit"])]
+    prefer_common: bool,
+
+    /// Find the ladder whose moves cost the least in total, rather than the
+    /// shortest one, and print what each move cost
+    #[arg(long, global = true, conflicts_with_all = ["all", "count", "k", "via", "visit", "prefer_common"])]
+    cheapest: bool,
+
+    /// The cost of every move, with --cheapest
+    #[arg(long, value_name = "N", default_value_t = 1, requires = "cheapest", global = true)]
+    move_cost: u32,
+
+    /// The extra cost of a move that changes a vowel or changes a letter into
+    /// one, with --cheapest
+    #[arg(long, value_name = "N", default_value_t = 0, requires = "cheapest", global = true)]
+    vowel_cost: u32,
+
+    /// The extra cost of changing the same position as the move before, with
+    /// --cheapest
+    #[arg(long, value_name = "N", default_value_t = 0, requires = "cheapest", global = true)]
+    repeat_cost: u32,
+
+    /// Words the ladder must not use, separated by commas
+    #[arg(long, value_name = "WORDS", value_delimiter = ',', global = true)]
+    exclude: Vec<String>,
+
+    /// Newline-separated list of words the ladder must not use
+    #[arg(long, value_name = "PATH", global = true)]
+    exclude_file: Option<PathBuf>,
+
+    /// Letters the ladder must not use; any word containing one is excluded
+    #[arg(long, value_name = "LETTERS", global = true)]
+    ban_letters: Option<String>,
+}
+
+/// What to do instead of finding a ladder between two words
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Write the word graph, or a part of it, as Graphviz DOT or GraphML
+    Export(ExportArgs),
+    /// Find ladders for many pairs of words, building the graph only once
+    Batch(BatchArgs),
+}
+
+/// The options of the `export` subcommand. Without any of `--component`,
+/// `--hops` or `--ladders`, the whole graph is exported.
+#[derive(Args, Debug)]
+struct ExportArgs {
+    /// Word the exported part of the graph is taken around
+    start: Option<String>,
+
+    /// Ending word; a shortest ladder from the starting word to it is
+    /// highlighted
+    goal: Option<String>,
+
+    /// Export every word a ladder from the starting word can reach
+    #[arg(long, requires = "start", conflicts_with_all = ["hops", "ladders"])]
+    component: bool,
+
+    /// Export every word at most N moves away from the starting word
+    #[arg(long, value_name = "N", requires = "start", conflicts_with = "ladders")]
+    hops: Option<usize>,
+
+    /// Export every word and move on a shortest ladder from the starting word
+    /// to the ending word
+    #[arg(long, requires = "goal")]
+    ladders: bool,
+
+    /// File format to write the graph in
+    #[arg(
+        long,
+        default_value_t = ExportFormat::default(),
+        value_parser = one_of(&ExportFormat::ALL, ExportFormat::name),
+    )]
+    to: ExportFormat,
+
+    /// File to write to instead of stdout
+    #[arg(long, short, value_name = "PATH")]
+    output: Option<PathBuf>,
+}
+
+/// The options of the `batch` subcommand. The other options apply to every
+/// pair of words, and the answers are printed in the same order as the pairs.
+#[derive(Args, Debug)]
+struct BatchArgs {
+    /// File with a starting and an ending word on each line, separated by
+    /// whitespace or a comma; `-` reads them from stdin
+    #[arg(default_value = "-")]
+    input: PathBuf,
+
+    /// Number of threads to solve the pairs with, or 0 for one per CPU
+    #[arg(long, short, value_name = "N", default_value_t = 1)]
+    jobs: usize,
+}
@@ -0,0 +1,91 @@
+use std::fmt;
+
+/// The kinds of move that can take a ladder from one word to the next. When
+/// two words are more than one kind of move apart, the edge between them is
+/// labelled with the kind that comes first here, which is the most specific.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum MoveKind {
+    /// Change one letter into another, as in `word -> work`
+    Substitute,
+    /// Add a letter anywhere in the word, or take one away, as in
+    /// `cat -> cart`. The two are the same move made in opposite directions,
+    /// so they are always allowed together.
+    InsertDelete,
+    /// Swap two neighbouring letters, as in `form -> from`
+    Swap,
+    /// Rearrange the letters in any order, as in `stop -> pots`
+    Anagram,
+}
+
+impl MoveKind {
+    /// Every kind of move, in the order they are listed in help text
+    pub const ALL: [MoveKind; 4] = [MoveKind::Substitute, MoveKind::InsertDelete, MoveKind::Swap, MoveKind::Anagram];
+
+    /// The name used for the kind of move on the command line
+    pub fn name(self) -> &'static str {
+        match self {
+            MoveKind::Substitute => "substitute",
+            MoveKind::InsertDelete => "insert-delete",
+            MoveKind::Swap => "swap",
+            MoveKind::Anagram => "anagram",
+        }
+    }
+
+    /// The bit standing for this kind of move in a [`MoveSet`]
+    fn bit(self) -> u8 {
+        1 << self as u8
+    }
+}
+
+impl fmt::Display for MoveKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// The kinds of move a [`WordGraph`](crate::WordGraph) connects words by.
+/// The default is the classic word ladder, where the only move is changing a
+/// single letter, so every word on a ladder has the same length.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MoveSet {
+    bits: u8,
+}
+
+impl MoveSet {
+    /// The set of the given kinds of move
+    pub fn new(kinds: &[MoveKind]) -> Self {
+        MoveSet { bits: kinds.iter().fold(0, |bits, kind| bits | kind.bit()) }
+    }
+
+    /// Returns whether `kind` is one of the moves in the set
+    pub fn contains(self, kind: MoveKind) -> bool {
+        self.bits & kind.bit() != 0
+    }
+
+    /// Every kind of move in the set
+    pub fn kinds(self) -> impl Iterator<Item = MoveKind> {
+        MoveKind::ALL.into_iter().filter(move |&kind| self.contains(kind))
+    }
+
+    /// Returns whether the moves can take a ladder from a word to a word of
+    /// a different length
+    pub fn changes_length(self) -> bool {
+        self.contains(MoveKind::InsertDelete)
+    }
+
+    /// The set as a single byte, with one bit for each kind of move
+    pub(crate) fn bits(self) -> u8 {
+        self.bits
+    }
+
+    /// The set whose byte is `bits`, if every bit stands for a kind of move
+    pub(crate) fn from_bits(bits: u8) -> Option<Self> {
+        (bits >> MoveKind::ALL.len() == 0).then_some(MoveSet { bits })
+    }
+}
+
+impl Default for MoveSet {
+    fn default() -> Self {
+        MoveSet::new(&[MoveKind::Substitute])
+    }
+}
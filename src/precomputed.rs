@@ -0,0 +1,38 @@
+use crate::dict::DICT;
+use crate::graph::Graph;
+use crate::moves::{MoveKind, MoveSet};
+
+// The graph of the built-in dictionary with the default moves, written by
+// the build script as `DICT_OFFSETS` and `DICT_TARGETS`
+include!(concat!(env!("OUT_DIR"), "/dict_graph.rs"));
+
+/// The graph of `dict` with the given moves, if the build script already
+/// worked it out: that is, if `dict` is the built-in dictionary and the moves
+/// are the default ones. Only the lookup table and the components are left
+/// to work out at runtime, both in a single pass over the graph.
+pub(crate) fn precomputed_graph<'a>(dict: &[&'a str], moves: MoveSet) -> Option<Graph<'a>> {
+    if moves != MoveSet::default() || dict != DICT.as_slice() {
+        return None
+    }
+    let kinds = vec![MoveKind::Substitute; DICT_TARGETS.len()];
+
+    Some(Graph::from_parts(dict.to_vec(), DICT_OFFSETS.to_vec(), DICT_TARGETS.to_vec(), kinds, moves))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WordGraph;
+
+    #[test]
+    fn precomputed_graph_matches_the_pairwise_one() {
+        assert!(precomputed_graph(&DICT, MoveSet::default()).is_some());
+        assert_eq!(WordGraph::from_dict(&DICT), WordGraph::from_dict_pairwise(&DICT));
+    }
+
+    #[test]
+    fn other_dictionaries_and_moves_are_built() {
+        assert!(precomputed_graph(&DICT[1..], MoveSet::default()).is_none());
+        assert!(precomputed_graph(&DICT, MoveSet::new(&[MoveKind::Substitute, MoveKind::Swap])).is_none());
+    }
+}
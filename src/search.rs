@@ -0,0 +1,343 @@
+use std::collections::VecDeque;
+use std::fmt;
+use std::mem;
+
+use crate::astar::{astar, ida_star};
+use crate::bidirectional::bidirectional_bfs;
+use crate::bignum::BigUint;
+use crate::bitset::BitSet;
+use crate::dag::{shortest_path_dag, Dag};
+use crate::error::SolveError;
+use crate::graph::{Subgraph, WordId};
+use crate::validate::validate;
+use crate::yen::k_shortest_paths;
+
+/// A simple wrapper around the rust VecDeque type, in order to match the
+/// `enqueue` and `dequeue` functions used in the breadth-first search
+/// pseudocode
+pub(crate) struct Queue<T> {
+    queue: VecDeque<T>,
+}
+
+impl<T> Queue<T> {
+    pub(crate) fn new() -> Self {
+        Queue { queue: VecDeque::new() }
+    }
+
+    pub(crate) fn enqueue(&mut self, val: T) {
+        self.queue.push_back(val);
+    }
+
+    pub(crate) fn dequeue(&mut self) -> Option<T> {
+        self.queue.pop_front()
+    }
+}
+
+/// Marks a word that has no parent in the parent map, either because it
+/// hasn't been reached yet or because it is the root of the search
+pub(crate) const NO_PARENT: WordId = WordId::MAX;
+
+/// Marks a word that a search hasn't reached yet, or a pair of words with no
+/// ladder between them, wherever distances are kept
+pub(crate) const UNREACHED: u32 = u32::MAX;
+
+/// A general breadth-first search algorithm defined on our `Graph` type.
+/// Because every word in the graph has a small integer id, we can attach a
+/// parent to each node after all: the parent map is simply an array indexed by
+/// word id, and the set of explored words is a bitset of the same size. We are
+/// guaranteed to not overwrite a parent at any point because a breadth-first
+/// search such as this is constructing a tree where each node has exactly one parent.
+/// If the queue runs dry before we reach the goal, the two words are in different
+/// components and we return `None`.
+///
+/// Pseudocode to be translated into Rust code
+///
+/// ```text
+///     procedure BFS(G, root) is
+///         let Q be a queue
+///         label root as explored
+///         Q.enqueue(root)
+///         while Q is not empty do
+///             v = Q.dequeue()
+///             if v is the goal then
+///                 return v
+///             for all edges from v to w in G.adjacentEdges(v) do
+///                 if w is n ot labeled as explored then
+///                     label w as explored
+///                     w.parent = v
+///                     Q.enqueue(w)
+/// ```
+pub(crate) fn bfs(graph: Subgraph, root: WordId, goal: WordId, stats: &mut SearchStats) -> Option<Vec<WordId>> {
+    bfs_from(graph, &[root], goal, stats)
+}
+
+/// Our `bfs` started from several words at once, as if they made up the first
+/// layer of the search. The ladder found starts from whichever of them is
+/// closest to `goal`, the earliest of them on a tie, and since none of the
+/// roots has a parent, walking back from the goal stops at that one.
+pub(crate) fn bfs_from(
+    graph: Subgraph,
+    roots: &[WordId],
+    goal: WordId,
+    stats: &mut SearchStats,
+) -> Option<Vec<WordId>> {
+    let mut q = Queue::new();
+    let mut visited = BitSet::new(graph.len());
+    let mut parent_map = vec![NO_PARENT; graph.len()];
+    for &root in roots {
+        if visited.insert(root) {
+            q.enqueue(root);
+        }
+    }
+    while let Some(v) = q.dequeue() {
+        if v == goal {
+            return Some(parent_map)
+        }
+        stats.expanded += 1;
+
+        for entry in graph.neighbors(v) {
+            if visited.insert(entry) {
+                parent_map[entry as usize] = v;
+                q.enqueue(entry);
+            }
+        }
+    }
+
+    None
+}
+
+/// Our `bfs` extended to count the shortest paths to every word it reaches,
+/// without ever building the paths themselves. Every shortest path to `w`
+/// arrives through one of the words in the layer just before it, so the number
+/// of shortest paths to `w` is the sum of the counts of those words. Since a
+/// word is only dequeued after the whole layer before it has been, its count
+/// is complete by the time it is dequeued, and in particular the goal's count
+/// is complete as soon as we dequeue the goal.
+pub(crate) fn count_paths_bfs(graph: Subgraph, root: WordId, goal: WordId, stats: &mut SearchStats) -> Option<BigUint> {
+    let mut q = Queue::new();
+    let mut distance = vec![UNREACHED; graph.len()];
+    let mut counts = vec![BigUint::zero(); graph.len()];
+    distance[root as usize] = 0;
+    counts[root as usize] = BigUint::one();
+    q.enqueue(root);
+    while let Some(v) = q.dequeue() {
+        // nothing reads the count of `v` again once it has been passed on
+        let count = mem::take(&mut counts[v as usize]);
+        if v == goal {
+            return Some(count)
+        }
+        stats.expanded += 1;
+
+        let depth = distance[v as usize];
+        for entry in graph.neighbors(v) {
+            if distance[entry as usize] == UNREACHED {
+                distance[entry as usize] = depth + 1;
+                q.enqueue(entry);
+            }
+            if distance[entry as usize] == depth + 1 {
+                counts[entry as usize] += &count;
+            }
+        }
+    }
+
+    None
+}
+
+/// Returns whether there is any ladder from `root` to `goal`, using a
+/// breadth-first search that keeps nothing but the set of explored words
+pub(crate) fn reachable(graph: Subgraph, root: WordId, goal: WordId) -> bool {
+    let mut q = Queue::new();
+    let mut visited = BitSet::new(graph.len());
+    visited.insert(root);
+    q.enqueue(root);
+    while let Some(v) = q.dequeue() {
+        if v == goal {
+            return true
+        }
+        for entry in graph.neighbors(v) {
+            if visited.insert(entry) {
+                q.enqueue(entry);
+            }
+        }
+    }
+
+    false
+}
+
+/// Walks a parent map back from `end` to `start` and returns the path between
+/// them in order, from `start` to `end`
+pub(crate) fn walk_parents(parent_map: &[WordId], start: WordId, end: WordId) -> Vec<WordId> {
+    let mut ptr = end;
+    let mut path = Vec::new();
+    while ptr != start {
+        path.push(ptr);
+        ptr = parent_map[ptr as usize];
+    }
+    path.push(start);
+    path.reverse();
+
+    path
+}
+
+/// Counters collected while searching for a ladder, so that the strategies
+/// can be compared with one another
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct SearchStats {
+    /// The number of words whose neighbours were examined. Strategies that
+    /// revisit words, like IDA*, count every visit.
+    pub expanded: usize,
+}
+
+/// The ways a shortest ladder can be searched for. They all find a ladder of
+/// the same, shortest length, but they differ in how much of the graph they
+/// explore to get there.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Strategy {
+    /// Breadth-first search from both ends at once, meeting in the middle
+    #[default]
+    Bidirectional,
+    /// Breadth-first search outward from the start word until the goal is found
+    Bfs,
+    /// A* search, expanding the words with the fewest letters left to change first
+    AStar,
+    /// Iterative-deepening A*, which needs memory only for the current ladder
+    IdaStar,
+}
+
+impl Strategy {
+    /// Every strategy, in the order they are listed in help text
+    pub const ALL: [Strategy; 4] = [Strategy::Bidirectional, Strategy::Bfs, Strategy::AStar, Strategy::IdaStar];
+
+    /// The name used for the strategy on the command line
+    pub fn name(self) -> &'static str {
+        match self {
+            Strategy::Bfs => "bfs",
+            Strategy::Bidirectional => "bidirectional",
+            Strategy::AStar => "astar",
+            Strategy::IdaStar => "idastar",
+        }
+    }
+}
+
+impl fmt::Display for Strategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// The error for two valid words with no ladder between them
+pub(crate) fn no_path_error(graph: Subgraph, start: WordId, end: WordId) -> SolveError {
+    SolveError::NoPathExists { start: graph.word(start).to_string(), goal: graph.word(end).to_string() }
+}
+
+/// Runs the chosen search strategy between two words that are already known
+/// to be in the graph, returning the ids of the words on the ladder
+pub(crate) fn search(
+    graph: Subgraph,
+    start: WordId,
+    end: WordId,
+    strategy: Strategy,
+    stats: &mut SearchStats,
+) -> Option<Vec<WordId>> {
+    // IDA* in particular would never finish if it had to prove there is no
+    // ladder by searching, so we rule that out before any strategy starts.
+    // Once words have been removed, being in the same component of the whole
+    // graph is no longer enough, so IDA* needs a proper check.
+    if !graph.connected(start, end) {
+        return None
+    }
+    if strategy == Strategy::IdaStar && graph.is_restricted() && !reachable(graph, start, end) {
+        return None
+    }
+
+    match strategy {
+        Strategy::Bfs => bfs(graph, start, end, stats).map(|parent_map| walk_parents(&parent_map, start, end)),
+        Strategy::Bidirectional => bidirectional_bfs(graph, start, end, stats),
+        Strategy::AStar => astar(graph, start, end, stats),
+        Strategy::IdaStar => ida_star(graph, start, end, stats),
+    }
+}
+
+/// Use one of our search implementations to get the result we actually want: the
+/// solution path. For `bfs` this simply requires taking the parent map, the end word,
+/// and the start word, and walking backward from there to construct the actual solution
+/// path. Both words are validated against the graph first, words in different
+/// components are rejected without searching at all, and the path is turned
+/// back from ids into the words of the dictionary.
+pub(crate) fn find_shortest_path<'g>(
+    graph: Subgraph<'_, 'g>,
+    start: &str,
+    end: &str,
+    strategy: Strategy,
+) -> Result<(Vec<&'g str>, SearchStats), SolveError> {
+    let (start, end) = validate(graph, start, end)?;
+
+    let mut stats = SearchStats::default();
+    let path = search(graph, start, end, strategy, &mut stats).ok_or_else(|| no_path_error(graph, start, end))?;
+
+    Ok((path.into_iter().map(|id| graph.word(id)).collect(), stats))
+}
+
+/// Validates the two words and then builds the graph of every shortest ladder
+/// between them with `shortest_path_dag`
+pub(crate) fn find_all_shortest_paths(graph: Subgraph, start: &str, end: &str) -> Result<Dag, SolveError> {
+    let (start, end) = validate(graph, start, end)?;
+    if !graph.connected(start, end) {
+        return Err(no_path_error(graph, start, end))
+    }
+
+    shortest_path_dag(graph, start, end).ok_or_else(|| no_path_error(graph, start, end))
+}
+
+/// Validates the two words and then counts the shortest ladders between them
+/// with `count_paths_bfs`. Words in different components have no ladders, which
+/// is reported as an error rather than a count of zero, just like everywhere else.
+pub(crate) fn count_shortest_paths(graph: Subgraph, start: &str, end: &str) -> Result<BigUint, SolveError> {
+    let (start, end) = validate(graph, start, end)?;
+    if !graph.connected(start, end) {
+        return Err(no_path_error(graph, start, end))
+    }
+
+    let mut stats = SearchStats::default();
+    count_paths_bfs(graph, start, end, &mut stats).ok_or_else(|| no_path_error(graph, start, end))
+}
+
+/// Validates the two words and then finds up to `k` of the shortest loopless
+/// ladders between them with `k_shortest_paths`, shortest first
+pub(crate) fn find_k_shortest_paths<'g>(
+    graph: Subgraph<'_, 'g>,
+    start: &str,
+    end: &str,
+    k: usize,
+) -> Result<Vec<(Vec<&'g str>, SearchStats)>, SolveError> {
+    let (start, end) = validate(graph, start, end)?;
+    if !graph.connected(start, end) {
+        return Err(no_path_error(graph, start, end))
+    }
+
+    Ok(k_shortest_paths(graph, start, end, k)
+        .into_iter()
+        .map(|(path, stats)| (path.into_iter().map(|id| graph.word(id)).collect(), stats))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dict::DICT;
+    use crate::{SolveError, WordGraph};
+
+    #[test]
+    fn count_matches_the_ladders_listed() {
+        let graph = WordGraph::from_dict(&DICT);
+        let pairs = [("word", "dork"), ("cold", "warm"), ("ywis", "word"), ("aahs", "zyme"), ("word", "word")];
+        for (start, goal) in pairs {
+            let count = graph.count_shortest_paths(start, goal).unwrap();
+            let listed = graph.all_shortest_paths(start, goal).unwrap().iter().count();
+            assert_eq!(count.to_u64(), Some(listed as u64), "from {} to {}", start, goal);
+        }
+
+        let apart = graph.count_shortest_paths("abri", "word");
+        assert!(matches!(apart, Err(SolveError::NoPathExists { .. })));
+        assert!(graph.all_shortest_paths("abri", "word").is_err());
+    }
+}
@@ -0,0 +1,91 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::dictionary::Dictionary;
+
+/// How common a word is. Puzzles like weaver only pick common words as the
+/// start and goal, but accept a much longer list of words along the way, and
+/// a longer list still holds words that are valid but rarely seen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum Tier {
+    /// Everyday words, the kind a puzzle would pick as its start or goal
+    Common,
+    /// Words a puzzle accepts on a ladder
+    #[default]
+    Allowed,
+    /// Valid but obscure words
+    Obscure,
+}
+
+impl Tier {
+    /// Every tier, from the most common to the most obscure
+    pub const ALL: [Tier; 3] = [Tier::Common, Tier::Allowed, Tier::Obscure];
+
+    /// The name used for the tier on the command line
+    pub fn name(self) -> &'static str {
+        match self {
+            Tier::Common => "common",
+            Tier::Allowed => "allowed",
+            Tier::Obscure => "obscure",
+        }
+    }
+}
+
+impl fmt::Display for Tier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// A [`Dictionary`] whose words are sorted into [`Tier`]s. Word lists for
+/// each tier are added on top of the dictionary it starts from with
+/// [`TieredDictionary::add_tier`], and the words of the starting dictionary
+/// that no list mentions are [`Tier::Allowed`]. A word added to more than one
+/// tier belongs to the most common of them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TieredDictionary {
+    dictionary: Dictionary,
+    tiers: HashMap<String, Tier>,
+}
+
+impl TieredDictionary {
+    /// Puts every word of `dictionary` in [`Tier::Allowed`]
+    pub fn new(dictionary: Dictionary) -> Self {
+        TieredDictionary { dictionary, tiers: HashMap::new() }
+    }
+
+    /// Adds the words of `words` to the dictionary, in `tier`, unless they have
+    /// already been added to a more common tier
+    pub fn add_tier(&mut self, words: &Dictionary, tier: Tier) -> &mut Self {
+        for &word in words.words().iter() {
+            let current = self.tiers.entry(word.to_string()).or_insert(tier);
+            *current = (*current).min(tier);
+        }
+        self.dictionary = Dictionary::from_words(self.dictionary.words().into_iter().chain(words.words()));
+        self
+    }
+
+    /// Every word of every tier, to build a [`WordGraph`](crate::WordGraph)
+    /// from
+    pub fn dictionary(&self) -> &Dictionary {
+        &self.dictionary
+    }
+
+    /// The tier `word` belongs to, if it is in the dictionary at all
+    pub fn tier(&self, word: &str) -> Option<Tier> {
+        match self.tiers.get(word) {
+            Some(&tier) => Some(tier),
+            None if self.dictionary.contains(word) => Some(Tier::Allowed),
+            None => None,
+        }
+    }
+
+    /// Every word in a less common tier than `tier`
+    pub fn words_beyond(&self, tier: Tier) -> Vec<&str> {
+        self.dictionary
+            .words()
+            .into_iter()
+            .filter(|word| self.tier(word).is_some_and(|word_tier| word_tier > tier))
+            .collect()
+    }
+}
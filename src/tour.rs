@@ -0,0 +1,245 @@
+use crate::error::SolveError;
+use crate::graph::{Subgraph, WordId};
+use crate::search::{no_path_error, Queue, SearchStats, Strategy, UNREACHED};
+use crate::validate::validate_waypoints;
+use crate::waypoints::path_via;
+
+/// The largest number of waypoints whose order is found exactly. The exact
+/// search takes time exponential in the number of waypoints, and a dozen is
+/// still well under a second.
+const EXACT_LIMIT: usize = 12;
+
+/// The number of steps from `root` to every word of the graph, from a single
+/// breadth-first search
+fn distances_from(graph: Subgraph, root: WordId, stats: &mut SearchStats) -> Vec<u32> {
+    let mut distance = vec![UNREACHED; graph.len()];
+    let mut q = Queue::new();
+    distance[root as usize] = 0;
+    q.enqueue(root);
+    while let Some(v) = q.dequeue() {
+        stats.expanded += 1;
+        for w in graph.neighbors(v) {
+            if distance[w as usize] == UNREACHED {
+                distance[w as usize] = distance[v as usize] + 1;
+                q.enqueue(w);
+            }
+        }
+    }
+
+    distance
+}
+
+/// The length of the shortest ladder between every pair of `words`, with one
+/// breadth-first search from each of them. Ladders are undirected, so the
+/// matrix is symmetric.
+fn distance_matrix(graph: Subgraph, words: &[WordId], stats: &mut SearchStats) -> Vec<Vec<u32>> {
+    words
+        .iter()
+        .map(|&word| {
+            let distance = distances_from(graph, word, stats);
+            words.iter().map(|&other| distance[other as usize]).collect()
+        })
+        .collect()
+}
+
+/// The total length of visiting the waypoints in `order`, between the start
+/// (index `0` of `dist`) and the goal (index `n + 1`)
+fn order_length(dist: &[Vec<u32>], order: &[usize]) -> u32 {
+    let goal = dist.len() - 1;
+    let mut previous = 0;
+    let mut length = 0;
+    for &next in order.iter().chain([goal].iter()) {
+        length += dist[previous][next];
+        previous = next;
+    }
+
+    length
+}
+
+/// The Held-Karp dynamic program. `best[set][last]` is the shortest way to go
+/// from the start through exactly the waypoints in the bit set `set`, ending
+/// at waypoint `last`, so each entry only needs the entries for the set without
+/// `last`. Waypoints are numbered from 1 in `dist`, matching their bits shifted
+/// by one.
+fn exact_order(dist: &[Vec<u32>]) -> Vec<usize> {
+    let n = dist.len() - 2;
+    let goal = n + 1;
+    if n == 0 {
+        return Vec::new()
+    }
+
+    let full = (1 << n) - 1;
+    let mut best = vec![vec![UNREACHED; n]; full + 1];
+    let mut previous = vec![vec![usize::MAX; n]; full + 1];
+    for last in 0..n {
+        best[1 << last][last] = dist[0][last + 1];
+    }
+    for set in 1..=full {
+        for last in 0..n {
+            if set & (1 << last) == 0 || best[set][last] == UNREACHED {
+                continue
+            }
+            for next in 0..n {
+                if set & (1 << next) != 0 {
+                    continue
+                }
+                let length = best[set][last] + dist[last + 1][next + 1];
+                let grown = set | (1 << next);
+                if length < best[grown][next] {
+                    best[grown][next] = length;
+                    previous[grown][next] = last;
+                }
+            }
+        }
+    }
+
+    let mut last = (0..n).min_by_key(|&last| best[full][last].saturating_add(dist[last + 1][goal])).unwrap();
+    let mut set = full;
+    let mut order = Vec::with_capacity(n);
+    while set != 0 {
+        order.push(last + 1);
+        let before = previous[set][last];
+        set &= !(1 << last);
+        last = before;
+    }
+    order.reverse();
+
+    order
+}
+
+/// Too many waypoints for the exact search: start from the nearest
+/// unvisited waypoint each time, then improve the order with 2-opt, reversing
+/// any stretch of it that makes the whole ladder shorter, until none does.
+/// The result is usually close to the best order but not guaranteed to be it.
+fn heuristic_order(dist: &[Vec<u32>]) -> Vec<usize> {
+    let n = dist.len() - 2;
+    let mut order = Vec::with_capacity(n);
+    let mut unvisited: Vec<usize> = (1..=n).collect();
+    let mut current = 0;
+    while !unvisited.is_empty() {
+        let (i, _) = unvisited.iter().enumerate().min_by_key(|&(_, &next)| dist[current][next]).unwrap();
+        current = unvisited.remove(i);
+        order.push(current);
+    }
+
+    let mut length = order_length(dist, &order);
+    let mut improved = true;
+    while improved {
+        improved = false;
+        for i in 0..n {
+            for j in i + 1..n {
+                order[i..=j].reverse();
+                let reversed = order_length(dist, &order);
+                if reversed < length {
+                    length = reversed;
+                    improved = true;
+                } else {
+                    order[i..=j].reverse();
+                }
+            }
+        }
+    }
+
+    order
+}
+
+/// Finds a ladder from the first of `words` to the last that visits every
+/// word in between, in whichever order makes the ladder shortest. The length
+/// of the shortest ladder between every pair of the words is found first, and
+/// the best order over those lengths is found exactly for up to
+/// [`EXACT_LIMIT`] waypoints, and with a heuristic beyond that. The ladder
+/// itself is then built through the waypoints in that order, like one given
+/// with `find_path_via`, so it can come out longer than the order promised
+/// when the shortest ladders between two pairs of waypoints would share a word.
+pub(crate) fn find_best_tour<'g>(
+    graph: Subgraph<'_, 'g>,
+    words: &[&str],
+    strategy: Strategy,
+) -> Result<(Vec<&'g str>, SearchStats), SolveError> {
+    let waypoints = validate_waypoints(graph, words)?;
+
+    let mut stats = SearchStats::default();
+    let dist = distance_matrix(graph, &waypoints, &mut stats);
+    // ladders go both ways, so if every word can be reached from the start
+    // then every word can be reached from every other
+    if let Some(i) = dist[0].iter().position(|&d| d == UNREACHED) {
+        return Err(no_path_error(graph, waypoints[0], waypoints[i]))
+    }
+
+    let order = if waypoints.len() - 2 <= EXACT_LIMIT { exact_order(&dist) } else { heuristic_order(&dist) };
+    let mut ordered = Vec::with_capacity(waypoints.len());
+    ordered.push(waypoints[0]);
+    ordered.extend(order.into_iter().map(|i| waypoints[i]));
+    ordered.push(waypoints[waypoints.len() - 1]);
+    let path = path_via(graph, &ordered, strategy, &mut stats)?;
+
+    Ok((path.into_iter().map(|id| graph.word(id)).collect(), stats))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dict::DICT;
+    use crate::WordGraph;
+
+    /// Every order of `items`, for checking a search against all of them
+    fn permutations<'w>(items: &[&'w str]) -> Vec<Vec<&'w str>> {
+        if items.is_empty() {
+            return vec![Vec::new()]
+        }
+        let mut all = Vec::new();
+        for i in 0..items.len() {
+            let mut rest = items.to_vec();
+            let first = rest.remove(i);
+            for mut order in permutations(&rest) {
+                order.insert(0, first);
+                all.push(order);
+            }
+        }
+
+        all
+    }
+
+    #[test]
+    fn the_best_order_is_as_short_as_any_other() {
+        let graph = WordGraph::from_dict(&DICT);
+        let visit = ["card", "work", "wore", "cord", "fork"];
+        let ladder = graph.shortest_path_visiting("cold", &visit, "warm", Strategy::Bfs).unwrap();
+        let shortest = permutations(&visit)
+            .iter()
+            .filter_map(|order| graph.shortest_path_via("cold", order, "warm", Strategy::Bfs).ok())
+            .map(|ladder| ladder.steps())
+            .min();
+        assert_eq!(Some(ladder.steps()), shortest);
+    }
+
+    #[test]
+    fn many_waypoints_are_all_visited_once() {
+        let graph = WordGraph::from_dict(&DICT);
+        let mut visit: Vec<&str> = graph.neighbors("word").chain(graph.neighbors("cord")).collect();
+        visit.sort_unstable();
+        visit.dedup();
+        visit.retain(|&word| word != "word" && word != "cord");
+        assert!(visit.len() > EXACT_LIMIT);
+
+        let ladder = graph.shortest_path_visiting("word", &visit, "cord", Strategy::Bfs).unwrap();
+        let words = ladder.words();
+        assert_eq!((words[0], words[words.len() - 1]), ("word", "cord"));
+        assert!(visit.iter().all(|word| words.contains(word)));
+        assert!(words.windows(2).all(|pair| graph.move_kind(pair[0], pair[1]).is_some()));
+        let mut distinct = words.to_vec();
+        distinct.sort_unstable();
+        distinct.dedup();
+        assert_eq!(distinct.len(), words.len());
+    }
+
+    #[test]
+    fn the_heuristic_follows_waypoints_along_a_line() {
+        // waypoints at these places along a straight ladder from 0 to 20,
+        // where the best order just visits them from left to right
+        let places: [u32; 15] = [0, 7, 3, 19, 11, 1, 15, 5, 9, 13, 2, 17, 6, 14, 20];
+        let dist: Vec<Vec<u32>> = places.iter().map(|&a| places.iter().map(|&b| a.abs_diff(b)).collect()).collect();
+        assert!(places.len() - 2 > EXACT_LIMIT);
+        assert_eq!(order_length(&dist, &heuristic_order(&dist)), 20);
+    }
+}
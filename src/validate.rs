@@ -0,0 +1,142 @@
+use crate::error::SolveError;
+use crate::graph::{Subgraph, WordId};
+
+/// Words in the dictionary are made up of lowercase ASCII letters only
+pub(crate) fn is_allowed_char(c: char) -> bool {
+    c.is_ascii_lowercase()
+}
+
+/// Puts a word given as input in the same form as the words of a
+/// [`Dictionary`](crate::Dictionary): trimmed and lowercased
+pub(crate) fn normalize(word: &str) -> String {
+    word.trim().to_lowercase()
+}
+
+/// Returns whether `word` could be a dictionary word at all: it isn't empty
+/// and uses only the letters the dictionary does
+fn is_spelled_right(word: &str) -> bool {
+    !word.is_empty() && word.chars().all(is_allowed_char)
+}
+
+/// Checks a single input word, returning its id in the graph if it is valid.
+/// `unknown` builds the error used when the word is spelled with allowed
+/// letters but simply isn't in the dictionary.
+fn check_word(
+    graph: Subgraph,
+    word: &str,
+    unknown: fn(String) -> SolveError,
+    problems: &mut Vec<SolveError>,
+) -> Option<WordId> {
+    if !is_spelled_right(word) {
+        problems.push(SolveError::InvalidCharacters(word.to_string()));
+        return None
+    }
+    match graph.id(word) {
+        Some(id) if graph.is_removed(id) => {
+            problems.push(SolveError::ExcludedWord(word.to_string()));
+            None
+        }
+        Some(id) => Some(id),
+        None => {
+            problems.push(unknown(word.to_string()));
+            None
+        }
+    }
+}
+
+/// Checks the start and goal words before any searching happens. Rather than
+/// stopping at the first problem, every check is run so that the caller can be
+/// told about everything that is wrong with the input at once. The words are
+/// normalized first, just like the words of a dictionary are, so `Word`
+/// finds `word`. On success the ids of the two words are returned.
+pub(crate) fn validate(graph: Subgraph, start: &str, goal: &str) -> Result<(WordId, WordId), SolveError> {
+    let (start, goal) = (normalize(start), normalize(goal));
+    let mut problems = Vec::new();
+    let canonical_start = check_word(graph, &start, SolveError::UnknownStartWord, &mut problems);
+    let canonical_goal = check_word(graph, &goal, SolveError::UnknownGoalWord, &mut problems);
+    // the lengths of a word with the wrong letters mean nothing, so only
+    // compare words that are spelled right
+    let comparable = is_spelled_right(&start) && is_spelled_right(&goal);
+    if comparable && !graph.moves().changes_length() && start.chars().count() != goal.chars().count() {
+        problems.push(SolveError::LengthMismatch { start: start.to_string(), goal: goal.to_string() });
+    }
+
+    match (canonical_start, canonical_goal) {
+        (Some(start), Some(goal)) if problems.is_empty() => Ok((start, goal)),
+        _ if problems.len() == 1 => Err(problems.remove(0)),
+        _ => Err(SolveError::InvalidInput(problems)),
+    }
+}
+
+/// Checks every word a ladder has to visit, in order: the start word, any
+/// waypoints and the goal word. As in [`validate`], the words are normalized
+/// first, and all of them must have the same length as the start word unless
+/// the moves can change it, and on top of that none of them may appear twice,
+/// since a ladder never visits a word twice. Lengths are only compared
+/// between words that are spelled right. On success the ids of the words are
+/// returned in the same order.
+pub(crate) fn validate_waypoints(graph: Subgraph, words: &[&str]) -> Result<Vec<WordId>, SolveError> {
+    let words: Vec<String> = words.iter().map(|word| normalize(word)).collect();
+    let words: Vec<&str> = words.iter().map(String::as_str).collect();
+    let mut problems = Vec::new();
+    let last = words.len() - 1;
+    let ids: Vec<Option<WordId>> = words
+        .iter()
+        .enumerate()
+        .map(|(i, word)| {
+            let unknown = match i {
+                0 => SolveError::UnknownStartWord,
+                i if i == last => SolveError::UnknownGoalWord,
+                _ => SolveError::UnknownWaypoint,
+            };
+            check_word(graph, word, unknown, &mut problems)
+        })
+        .collect();
+
+    let start = words[0];
+    for &word in words[1..].iter().filter(|&&word| is_spelled_right(start) && is_spelled_right(word)) {
+        if !graph.moves().changes_length() && word.chars().count() != start.chars().count() {
+            problems.push(SolveError::LengthMismatch { start: start.to_string(), goal: word.to_string() });
+        }
+    }
+    for (i, &word) in words.iter().enumerate() {
+        // only report each repeated word once, at its second appearance
+        if words[..i].iter().filter(|&&earlier| earlier == word).count() == 1 {
+            problems.push(SolveError::RepeatedWaypoint(word.to_string()));
+        }
+    }
+
+    match ids.into_iter().collect::<Option<Vec<WordId>>>() {
+        Some(ids) if problems.is_empty() => Ok(ids),
+        _ if problems.len() == 1 => Err(problems.remove(0)),
+        _ => Err(SolveError::InvalidInput(problems)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dict::DICT;
+    use crate::{SolveError, Strategy, WordGraph};
+
+    #[test]
+    fn words_are_normalized_like_the_dictionary() {
+        let graph = WordGraph::from_dict(&DICT);
+        assert_eq!(graph.shortest_path("Word", " DORK ").unwrap().words(), ["word", "work", "dork"]);
+        assert_eq!(graph.shortest_path_via("COLD", &["Cord"], "warm", Strategy::Bfs).unwrap().words()[1], "cord");
+        assert_eq!(graph.shortest_path("w0rd", "dork"), Err(SolveError::InvalidCharacters("w0rd".to_string())));
+    }
+
+    #[test]
+    fn lengths_are_only_compared_between_words_spelled_right() {
+        let graph = WordGraph::from_dict(&DICT);
+        let invalid = Err(SolveError::InvalidCharacters(String::new()));
+        assert_eq!(graph.shortest_path("", "word"), invalid);
+        assert_eq!(graph.shortest_path_via("", &["work"], "dork", Strategy::Bfs), invalid);
+
+        let mismatch = SolveError::LengthMismatch { start: "word".to_string(), goal: "words".to_string() };
+        let Err(SolveError::InvalidInput(problems)) = graph.shortest_path("word", "words") else {
+            panic!("a longer unknown word should give two problems")
+        };
+        assert_eq!(problems, [SolveError::UnknownGoalWord("words".to_string()), mismatch]);
+    }
+}
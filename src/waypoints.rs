@@ -0,0 +1,65 @@
+use crate::error::SolveError;
+use crate::graph::{Subgraph, WordId};
+use crate::search::{no_path_error, reachable, search, SearchStats, Strategy};
+use crate::validate::validate_waypoints;
+
+/// Finds a ladder from the first of `words` to the last that passes through
+/// every word in between, in the order given. The ladder is made by joining a
+/// shortest ladder for each segment between consecutive waypoints, and since
+/// a ladder never visits a word twice, each segment is searched without the
+/// words already used by the segments before it and without the waypoints
+/// that are still to come. Segments are solved one after another, so the
+/// ladder is not necessarily the shortest one through all the waypoints, and
+/// a segment can turn out to be unreachable only because of the way an
+/// earlier one was taken.
+pub(crate) fn find_path_via<'g>(
+    graph: Subgraph<'_, 'g>,
+    words: &[&str],
+    strategy: Strategy,
+) -> Result<(Vec<&'g str>, SearchStats), SolveError> {
+    let waypoints = validate_waypoints(graph, words)?;
+    let mut stats = SearchStats::default();
+    let path = path_via(graph, &waypoints, strategy, &mut stats)?;
+
+    Ok((path.into_iter().map(|id| graph.word(id)).collect(), stats))
+}
+
+/// Joins the segments between the already validated `waypoints` into one
+/// ladder, as described for [`find_path_via`]
+pub(crate) fn path_via(
+    graph: Subgraph,
+    waypoints: &[WordId],
+    strategy: Strategy,
+    stats: &mut SearchStats,
+) -> Result<Vec<WordId>, SolveError> {
+    let mut removed = graph.removed_words();
+    for &waypoint in waypoints.iter() {
+        removed.insert(waypoint);
+    }
+    let mut path: Vec<WordId> = vec![waypoints[0]];
+    for segment in waypoints.windows(2) {
+        let (from, to) = (segment[0], segment[1]);
+        // the two ends of the segment are the only words it may use that
+        // another segment also uses
+        removed.remove(from);
+        removed.remove(to);
+        let segment_path = match search(Subgraph::without(graph.graph(), &removed), from, to, strategy, stats) {
+            Some(segment_path) => segment_path,
+            // tell a segment with no ladder at all apart from one whose every
+            // ladder is blocked by the words the ladder has already used
+            None if reachable(graph, from, to) => {
+                return Err(SolveError::WaypointBlocked {
+                    start: graph.word(from).to_string(),
+                    goal: graph.word(to).to_string(),
+                })
+            }
+            None => return Err(no_path_error(graph, from, to)),
+        };
+        for &id in segment_path.iter() {
+            removed.insert(id);
+        }
+        path.extend_from_slice(&segment_path[1..]);
+    }
+
+    Ok(path)
+}
@@ -0,0 +1,131 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashSet};
+
+use crate::graph::{Subgraph, WordId};
+use crate::search::{bfs, bfs_from, walk_parents, SearchStats, NO_PARENT};
+
+/// The shortest way from `spur` to `goal` in a graph that already leaves out
+/// the root of the ladder, `spur` included, and whose first move isn't to one
+/// of the `blocked` words. That is a breadth-first search from every other
+/// neighbour of the spur at once, with the spur put back in front.
+fn spur_path(
+    graph: Subgraph,
+    spur: WordId,
+    goal: WordId,
+    blocked: &[WordId],
+    stats: &mut SearchStats,
+) -> Option<Vec<WordId>> {
+    stats.expanded += 1;
+    let first_moves: Vec<WordId> = graph.neighbors(spur).filter(|w| !blocked.contains(w)).collect();
+    let parent_map = bfs_from(graph, &first_moves, goal, stats)?;
+    let mut path = vec![goal];
+    while parent_map[path[path.len() - 1] as usize] != NO_PARENT {
+        path.push(parent_map[path[path.len() - 1] as usize]);
+    }
+    path.push(spur);
+    path.reverse();
+
+    Some(path)
+}
+
+/// Yen's algorithm for the `k` shortest loopless ladders from `start` to
+/// `goal`, shortest first. The first ladder is an ordinary shortest one. Each
+/// later ladder must leave the ladders already found at some point, so for
+/// every word of the last ladder found we search for the shortest way to the
+/// goal from that "spur" word that shares the same prefix (the "root") but
+/// doesn't take any move an earlier ladder with that root took next, and never
+/// goes back through the root. The best of all these candidates is the next
+/// ladder. Ladders of equal length come out in dictionary order. Each ladder is
+/// returned with the counters of the search up to the point it was found.
+pub(crate) fn k_shortest_paths(
+    graph: Subgraph,
+    start: WordId,
+    goal: WordId,
+    k: usize,
+) -> Vec<(Vec<WordId>, SearchStats)> {
+    let mut stats = SearchStats::default();
+    let mut found: Vec<(Vec<WordId>, SearchStats)> = Vec::new();
+    if k == 0 {
+        return found
+    }
+    match bfs(graph, start, goal, &mut stats) {
+        Some(parent_map) => found.push((walk_parents(&parent_map, start, goal), stats)),
+        None => return found,
+    }
+
+    let mut candidates = BinaryHeap::new();
+    let mut seen: HashSet<Vec<WordId>> = HashSet::new();
+    seen.insert(found[0].0.clone());
+    while found.len() < k {
+        let last = found[found.len() - 1].0.clone();
+        let mut removed = graph.removed_words();
+        for i in 0..last.len() - 1 {
+            let spur = last[i];
+            let root = &last[..=i];
+            removed.insert(spur);
+            let blocked: Vec<WordId> = found
+                .iter()
+                .filter(|(path, _)| path.len() > i + 1 && &path[..=i] == root)
+                .map(|(path, _)| path[i + 1])
+                .collect();
+
+            let without_root = Subgraph::without(graph.graph(), &removed);
+            if let Some(spur_path) = spur_path(without_root, spur, goal, &blocked, &mut stats) {
+                let mut candidate = root[..i].to_vec();
+                candidate.extend(spur_path);
+                if seen.insert(candidate.clone()) {
+                    candidates.push(Reverse((candidate.len(), candidate)));
+                }
+            }
+        }
+
+        match candidates.pop() {
+            Some(Reverse((_, path))) => found.push((path, stats)),
+            None => break,
+        }
+    }
+
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::dict::DICT;
+    use crate::WordGraph;
+
+    #[test]
+    fn ladders_grow_and_never_repeat() {
+        let graph = WordGraph::from_dict(&DICT);
+        for (start, goal) in [("word", "dork"), ("cold", "warm"), ("ywis", "word"), ("word", "word")] {
+            let ladders = graph.k_shortest_paths(start, goal, 25).unwrap();
+            assert!(!ladders.is_empty());
+            assert!(ladders.windows(2).all(|pair| pair[0].steps() <= pair[1].steps()), "from {} to {}", start, goal);
+            let distinct: HashSet<&[&str]> = ladders.iter().map(|ladder| ladder.words()).collect();
+            assert_eq!(distinct.len(), ladders.len(), "from {} to {}", start, goal);
+            for ladder in ladders.iter() {
+                let words: HashSet<&str> = ladder.iter().collect();
+                assert_eq!(words.len(), ladder.words().len(), "{:?} repeats a word", ladder.words());
+                assert_eq!((ladder.words()[0], ladder.words()[ladder.steps()]), (start, goal));
+                assert!(ladder.words().windows(2).all(|step| graph.neighbors(step[0]).any(|word| word == step[1])));
+            }
+        }
+    }
+
+    #[test]
+    fn every_shortest_ladder_comes_first() {
+        let graph = WordGraph::from_dict(&DICT);
+        for (start, goal) in [("word", "dork"), ("cold", "warm"), ("ywis", "word")] {
+            let shortest = graph.all_shortest_paths(start, goal).unwrap();
+            let expected: HashSet<Vec<&str>> = shortest.iter().map(|ladder| ladder.words().to_vec()).collect();
+            let ladders = graph.k_shortest_paths(start, goal, expected.len() + 5).unwrap();
+            assert_eq!(ladders.len(), expected.len() + 5);
+
+            let (first, rest) = ladders.split_at(expected.len());
+            let first: HashSet<Vec<&str>> = first.iter().map(|ladder| ladder.words().to_vec()).collect();
+            assert_eq!(first, expected, "from {} to {}", start, goal);
+            assert!(rest.iter().all(|ladder| ladder.steps() > shortest.steps()));
+        }
+    }
+}